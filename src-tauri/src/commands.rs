@@ -36,13 +36,21 @@ pub async fn is_fullscreen(window: Window) -> Result<bool, String> {
 
 // Settings commands
 #[tauri::command]
-pub async fn get_settings() -> Result<serde_json::Value, String> {
+pub async fn get_settings() -> Result<storage::Settings, String> {
     storage::get_settings().await
 }
 
 #[tauri::command]
-pub async fn set_settings(settings: serde_json::Value) -> Result<(), String> {
-    storage::set_settings(settings).await
+pub async fn set_settings(app: tauri::AppHandle, settings: storage::Settings) -> Result<(), String> {
+    use tauri::Emitter;
+    let change = storage::set_settings(settings).await?;
+
+    // Подсистемы бэкенда подписываются на это событие через app.listen
+    if !change.changed.is_empty() {
+        app.emit("settings-changed", &change).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
 }
 
 // Bookmarks commands
@@ -56,6 +64,41 @@ pub async fn set_bookmarks(bookmarks: Vec<storage::Bookmark>) -> Result<(), Stri
     storage::set_bookmarks(bookmarks).await
 }
 
+#[tauri::command]
+pub async fn create_bookmark_folder(
+    title: String,
+    parent_id: Option<String>,
+) -> Result<storage::Bookmark, String> {
+    storage::create_bookmark_folder(title, parent_id).await
+}
+
+#[tauri::command]
+pub async fn move_bookmark(
+    id: String,
+    parent_id: Option<String>,
+    position: Option<usize>,
+) -> Result<Vec<storage::Bookmark>, String> {
+    storage::move_bookmark(id, parent_id, position).await
+}
+
+#[tauri::command]
+pub async fn reorder_bookmarks(
+    parent_id: Option<String>,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<storage::Bookmark>, String> {
+    storage::reorder_bookmarks(parent_id, ordered_ids).await
+}
+
+#[tauri::command]
+pub async fn get_bookmarks_by_tag(tag: String) -> Result<Vec<storage::Bookmark>, String> {
+    storage::get_bookmarks_by_tag(tag).await
+}
+
+#[tauri::command]
+pub async fn resolve_keyword(input: String) -> Result<Option<String>, String> {
+    storage::resolve_keyword(input).await
+}
+
 // History commands
 #[tauri::command]
 pub async fn get_history() -> Result<Vec<storage::HistoryEntry>, String> {
@@ -77,6 +120,53 @@ pub async fn set_history(history: Vec<storage::HistoryEntry>) -> Result<(), Stri
     storage::set_history(history).await
 }
 
+#[tauri::command]
+pub async fn search_history(
+    query: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<storage::SearchResults<storage::HistorySearchHit>, String> {
+    storage::search_history(query, limit, offset, from, to).await
+}
+
+#[tauri::command]
+pub async fn search_bookmarks(
+    query: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<storage::SearchResults<storage::BookmarkSearchHit>, String> {
+    storage::search_bookmarks(query, limit, offset).await
+}
+
+// Address bar autocomplete
+#[tauri::command]
+pub async fn autocomplete(
+    state: tauri::State<'_, AppState>,
+    input: String,
+    quick_sites: Option<Vec<storage::QuickSite>>,
+    limit: Option<u32>,
+) -> Result<Vec<storage::Suggestion>, String> {
+    let open_tabs = {
+        let manager = state.webview_manager.lock().map_err(|e| e.to_string())?;
+        manager
+            .list()
+            .iter()
+            .filter_map(|id| manager.get(id))
+            .filter(|info| !info.url.is_empty() && info.url != "about:blank")
+            .map(|info| storage::OpenTab {
+                id: info.id.clone(),
+                url: info.url.clone(),
+                title: info.title.clone(),
+                favicon: if info.favicon.is_empty() { None } else { Some(info.favicon.clone()) },
+            })
+            .collect::<Vec<_>>()
+    };
+
+    storage::autocomplete(input, open_tabs, quick_sites.unwrap_or_default(), limit).await
+}
+
 // External commands
 #[tauri::command]
 pub async fn open_external(app: tauri::AppHandle, url: String) -> Result<(), String> {
@@ -116,6 +206,14 @@ pub async fn show_error(app: tauri::AppHandle, title: String, message: String) -
 }
 
 // Export/Import bookmarks
+/// HTML-файл закладок (Netscape) определяем по расширению
+fn is_html_path(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"))
+        .unwrap_or(false)
+}
+
 #[tauri::command]
 pub async fn export_bookmarks(app: tauri::AppHandle, bookmarks: Vec<storage::Bookmark>) -> Result<bool, String> {
     use tauri_plugin_dialog::DialogExt;
@@ -125,12 +223,17 @@ pub async fn export_bookmarks(app: tauri::AppHandle, bookmarks: Vec<storage::Boo
         .set_title("Экспорт закладок")
         .set_file_name("bookmarks.json")
         .add_filter("JSON", &["json"])
+        .add_filter("HTML (Netscape)", &["html", "htm"])
         .blocking_save_file();
     
     if let Some(file_path) = path {
         if let Some(path) = file_path.as_path() {
-            let json = serde_json::to_string_pretty(&bookmarks).map_err(|e| e.to_string())?;
-            std::fs::write(path, json).map_err(|e| e.to_string())?;
+            let content = if is_html_path(path) {
+                storage::to_netscape_html(&bookmarks)
+            } else {
+                serde_json::to_string_pretty(&bookmarks).map_err(|e| e.to_string())?
+            };
+            std::fs::write(path, content).map_err(|e| e.to_string())?;
             Ok(true)
         } else {
             Ok(false)
@@ -148,12 +251,17 @@ pub async fn import_bookmarks(app: tauri::AppHandle) -> Result<Option<Vec<storag
         .file()
         .set_title("Импорт закладок")
         .add_filter("JSON", &["json"])
+        .add_filter("HTML (Netscape)", &["html", "htm"])
         .blocking_pick_file();
     
     if let Some(file_path) = path {
         if let Some(path) = file_path.as_path() {
             let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-            let bookmarks: Vec<storage::Bookmark> = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+            let bookmarks = if is_html_path(path) || storage::is_netscape_html(&content) {
+                storage::parse_netscape_html(&content)
+            } else {
+                serde_json::from_str(&content).map_err(|e| e.to_string())?
+            };
             Ok(Some(bookmarks))
         } else {
             Ok(None)
@@ -195,8 +303,14 @@ pub async fn start_download(
     app: tauri::AppHandle,
     url: String,
     filename: Option<String>,
+    expected_digest: Option<String>,
 ) -> Result<downloads::Download, String> {
-    downloads::start_download(app, url, filename).await
+    downloads::start_download(app, url, filename, expected_digest).await
+}
+
+#[tauri::command]
+pub async fn compute_download_hash(app: tauri::AppHandle, id: String, algo: String) -> Result<String, String> {
+    downloads::compute_download_hash(&app, &id, &algo).await
 }
 
 #[tauri::command]
@@ -205,21 +319,65 @@ pub async fn cancel_download(app: tauri::AppHandle, id: String) -> Result<(), St
 }
 
 #[tauri::command]
-pub async fn pause_download(_app: tauri::AppHandle, _id: String) -> Result<(), String> {
-    // TODO: Реализовать паузу (требует поддержки Range запросов)
-    Ok(())
+pub async fn pause_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    downloads::pause_download_by_id(&app, &id).await
 }
 
 #[tauri::command]
-pub async fn resume_download(_app: tauri::AppHandle, _id: String) -> Result<(), String> {
-    // TODO: Реализовать возобновление
-    Ok(())
+pub async fn resume_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    downloads::resume_download_by_id(&app, &id).await
 }
 
 #[tauri::command]
-pub async fn open_download(app: tauri::AppHandle, path: String) -> Result<(), String> {
+pub async fn resume_interrupted_downloads(app: tauri::AppHandle) -> Result<usize, String> {
+    downloads::resume_interrupted_downloads(&app).await
+}
+
+/// Загрузки, прерванные закрытием приложения в прошлый раз
+#[tauri::command]
+pub fn take_interrupted_downloads(app: tauri::AppHandle) -> Result<Vec<downloads::Download>, String> {
+    downloads::take_interrupted_on_startup(&app)
+}
+
+#[tauri::command]
+pub fn get_download_queue(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    downloads::queue_snapshot(&app)
+}
+
+/// Переместить ожидающую загрузку на позицию в очереди (0 — следующая)
+#[tauri::command]
+pub fn move_download_in_queue(app: tauri::AppHandle, id: String, position: usize) -> Result<(), String> {
+    downloads::move_in_queue(&app, &id, position)
+}
+
+/// Поднять загрузку в начало очереди
+#[tauri::command]
+pub fn prioritize_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    downloads::move_in_queue(&app, &id, 0)
+}
+
+/// Свой лимит скорости загрузки в байтах в секунду (`None` — общий из настроек)
+#[tauri::command]
+pub async fn set_download_speed_limit(
+    app: tauri::AppHandle,
+    id: String,
+    bytes_per_sec: Option<u64>,
+) -> Result<(), String> {
+    downloads::set_speed_limit_by_id(&app, &id, bytes_per_sec).await
+}
+
+/// Открыть скачанный файл. Путь берётся из истории загрузок: опасный файл
+/// откроется только после подтверждения
+#[tauri::command]
+pub async fn open_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
     use tauri_plugin_opener::OpenerExt;
-    app.opener().open_path(&path, None::<&str>).map_err(|e| e.to_string())
+    let path = downloads::openable_path(&id).await?;
+    app.opener().open_path(path.to_string_lossy(), None::<&str>).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn confirm_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    downloads::confirm_download_by_id(&app, &id).await
 }
 
 #[tauri::command]
@@ -261,9 +419,23 @@ pub async fn clear_completed_downloads() -> Result<(), String> {
 
 #[tauri::command]
 pub async fn get_downloads_folder() -> Result<String, String> {
-    dirs::download_dir()
+    downloads::default_downloads_dir()
+        .await
         .map(|p| p.to_string_lossy().to_string())
-        .ok_or_else(|| "Could not find downloads directory".to_string())
+}
+
+/// Выбрать папку для загрузок (или для правила загрузок)
+#[tauri::command]
+pub async fn pick_download_folder(app: tauri::AppHandle, current: Option<String>) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+    
+    let mut builder = app.dialog().file();
+    if let Some(current) = current.filter(|c| !c.is_empty()) {
+        builder = builder.set_directory(current);
+    }
+    
+    let path = builder.blocking_pick_folder();
+    Ok(path.and_then(|p| p.as_path().map(|path| path.to_string_lossy().to_string())))
 }
 
 // Browser import
@@ -308,3 +480,44 @@ pub async fn clear_session() -> Result<bool, String> {
     storage::clear_session().await
 }
 
+// Profile commands
+#[tauri::command]
+pub async fn list_profiles() -> Result<storage::ProfileList, String> {
+    storage::list_profiles().await
+}
+
+#[tauri::command]
+pub async fn create_profile(name: String) -> Result<storage::Profile, String> {
+    storage::create_profile(name).await
+}
+
+#[tauri::command]
+pub async fn rename_profile(id: String, name: String) -> Result<storage::Profile, String> {
+    storage::rename_profile(id, name).await
+}
+
+#[tauri::command]
+pub async fn delete_profile(id: String) -> Result<(), String> {
+    storage::delete_profile(id).await
+}
+
+/// Переключиться на другой профиль: данные и WebView привязаны к профилю
+/// на всё время работы процесса, поэтому приложение перезапускается в нём.
+/// Новый процесс запускается, только когда этот уже завершается, — иначе
+/// оба писали бы в общий `profiles.json`
+#[tauri::command]
+pub async fn switch_profile(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    if id == storage::active_profile_id() {
+        return Ok(());
+    }
+    let profile = storage::get_profile(id).await?;
+
+    *state.switch_to_profile.lock().map_err(|e| e.to_string())? = Some(profile.id);
+    app.exit(0);
+    Ok(())
+}
+
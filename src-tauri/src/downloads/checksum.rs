@@ -0,0 +1,184 @@
+//! Контрольные суммы загрузок (SHA-256, SHA-1, MD5)
+//!
+//! Сумма записывается как `<алгоритм>:<hex>`, например `sha256:9f86d0…`.
+//! При загрузке одним потоком она считается по ходу передачи, у сегментной
+//! загрузки — по готовому файлу.
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const READ_CHUNK: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha1,
+    Md5,
+}
+
+impl HashAlgorithm {
+    /// `sha256`, `sha-256`, `SHA1`, `md5`…
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().replace('-', "").as_str() {
+            "sha256" => Ok(Self::Sha256),
+            "sha1" => Ok(Self::Sha1),
+            "md5" => Ok(Self::Md5),
+            _ => Err(format!("Unsupported hash algorithm: {}", name)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha1 => "sha1",
+            Self::Md5 => "md5",
+        }
+    }
+
+    fn hex_len(self) -> usize {
+        match self {
+            Self::Sha256 => 64,
+            Self::Sha1 => 40,
+            Self::Md5 => 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: HashAlgorithm,
+    /// В нижнем регистре
+    pub hex: String,
+}
+
+impl Checksum {
+    /// `sha256:<hex>` или просто hex — тогда алгоритм определяется по длине
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (algorithm, hex) = match value.split_once(':') {
+            Some((name, hex)) => (HashAlgorithm::parse(name)?, hex.trim()),
+            None => {
+                let algorithm = [HashAlgorithm::Sha256, HashAlgorithm::Sha1, HashAlgorithm::Md5]
+                    .into_iter()
+                    .find(|a| a.hex_len() == value.len())
+                    .ok_or_else(|| format!("Cannot tell the hash algorithm of {:?}", value))?;
+                (algorithm, value)
+            }
+        };
+        if hex.len() != algorithm.hex_len() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid {} digest: {:?}", algorithm.name(), hex));
+        }
+        Ok(Self {
+            algorithm,
+            hex: hex.to_ascii_lowercase(),
+        })
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm.name(), self.hex)
+    }
+}
+
+/// Потоковое вычисление суммы
+pub enum Hasher {
+    Sha256(Sha256),
+    Sha1(Sha1),
+    Md5(Md5),
+}
+
+impl Hasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::Sha1 => Self::Sha1(Sha1::new()),
+            HashAlgorithm::Md5 => Self::Md5(Md5::new()),
+        }
+    }
+
+    /// Посчитать сумму уже записанного файла и продолжить с его конца
+    pub async fn from_file(algorithm: HashAlgorithm, path: &Path) -> Result<Self, String> {
+        let path = PathBuf::from(path);
+        tokio::task::spawn_blocking(move || {
+            let mut hasher = Self::new(algorithm);
+            let mut file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+            let mut buffer = vec![0; READ_CHUNK];
+            loop {
+                let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+                if read == 0 {
+                    return Ok(hasher);
+                }
+                hasher.update(&buffer[..read]);
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(h) => h.update(data),
+            Self::Sha1(h) => h.update(data),
+            Self::Md5(h) => h.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Checksum {
+        let (algorithm, bytes) = match self {
+            Self::Sha256(h) => (HashAlgorithm::Sha256, h.finalize().to_vec()),
+            Self::Sha1(h) => (HashAlgorithm::Sha1, h.finalize().to_vec()),
+            Self::Md5(h) => (HashAlgorithm::Md5, h.finalize().to_vec()),
+        };
+        Checksum {
+            algorithm,
+            hex: bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        }
+    }
+}
+
+/// Сумма файла целиком
+pub async fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Result<Checksum, String> {
+    Ok(Hasher::from_file(algorithm, path).await?.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_known_vectors() {
+        let cases = [
+            (HashAlgorithm::Sha256, "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (HashAlgorithm::Sha1, "sha1:a9993e364706816aba3e25717850c26c9cd0d89d"),
+            (HashAlgorithm::Md5, "md5:900150983cd24fb0d6963f7d28e17f72"),
+        ];
+        for (algorithm, expected) in cases {
+            let mut hasher = Hasher::new(algorithm);
+            hasher.update(b"a");
+            hasher.update(b"bc");
+            assert_eq!(hasher.finalize().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn parses_checksums() {
+        let sha256 = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+        assert_eq!(
+            Checksum::parse(&format!("SHA-256:{}", sha256)).unwrap().to_string(),
+            format!("sha256:{}", sha256.to_ascii_lowercase())
+        );
+        assert_eq!(Checksum::parse(sha256).unwrap().algorithm, HashAlgorithm::Sha256);
+        assert_eq!(Checksum::parse("a9993e364706816aba3e25717850c26c9cd0d89d").unwrap().algorithm, HashAlgorithm::Sha1);
+        assert_eq!(Checksum::parse(" md5:900150983cd24fb0d6963f7d28e17f72 ").unwrap().algorithm, HashAlgorithm::Md5);
+
+        assert!(Checksum::parse("sha256:abc").is_err());
+        assert!(Checksum::parse("crc32:900150983cd24fb0d6963f7d28e17f72").is_err());
+        assert!(Checksum::parse("md5:zz0150983cd24fb0d6963f7d28e17f72").is_err());
+        assert!(Checksum::parse("1234").is_err());
+    }
+}
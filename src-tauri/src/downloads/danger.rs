@@ -0,0 +1,299 @@
+//! Опасные загрузки
+//!
+//! Готовый файл проверяется по расширению и по первым байтам: исполняемые
+//! файлы (ELF, PE, Mach-O), скрипты, ZIP-архивы с исполняемыми файлами
+//! внутри и файлы, чьё содержимое исполняемое, а расширение выдаёт их за
+//! безобидные. Такие загрузки ждут подтверждения пользователя, прежде чем
+//! их можно открыть. Бит исполнения у скачанных файлов снимается всегда.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "msi", "msix", "msp", "appx", "com", "scr", "pif", "cpl", "msc", "dll", "sys", "lnk",
+    "reg", "jar", "apk", "app", "dmg", "pkg", "deb", "rpm", "appimage", "run", "so", "dylib",
+];
+
+const SCRIPT_EXTENSIONS: &[&str] = &[
+    "bat", "cmd", "ps1", "psm1", "vbs", "vbe", "js", "jse", "wsf", "wsh", "hta", "sh", "bash",
+    "zsh", "ksh", "csh", "command", "tool", "desktop", "py", "pyw", "pl", "rb", "applescript",
+    "scpt",
+];
+
+/// Сколько байт из начала файла смотрим
+const HEADER_LEN: usize = 4096;
+const ZIP_LOCAL_SIGNATURE: &[u8] = b"PK\x03\x04";
+const ZIP_CENTRAL_SIGNATURE: u32 = 0x0201_4b50;
+const ZIP_END_SIGNATURE: u32 = 0x0605_4b50;
+/// Запись конца центрального каталога без комментария
+const ZIP_END_LEN: usize = 22;
+/// Больший центральный каталог не читаем
+const MAX_CENTRAL_DIRECTORY: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Danger {
+    /// Программа или установщик
+    Executable,
+    /// Скрипт, который система запустит по двойному щелчку
+    Script,
+    /// Архив с исполняемыми файлами или скриптами
+    Archive,
+    /// Исполняемое содержимое под безобидным расширением
+    Mismatch,
+}
+
+impl Danger {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Executable => "executable",
+            Self::Script => "script",
+            Self::Archive => "archive",
+            Self::Mismatch => "mismatch",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Executable => "Executable file",
+            Self::Script => "Script file",
+            Self::Archive => "Archive contains executable files",
+            Self::Mismatch => "File content is executable but its extension says otherwise",
+        }
+    }
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+fn is_dangerous_name(name: &str) -> bool {
+    let extension = extension(name);
+    EXECUTABLE_EXTENSIONS.contains(&extension.as_str()) || SCRIPT_EXTENSIONS.contains(&extension.as_str())
+}
+
+/// ELF, PE (`MZ` с заголовком `PE\0\0`) или Mach-O
+fn is_executable_content(header: &[u8]) -> bool {
+    if header.starts_with(b"\x7fELF") {
+        return true;
+    }
+    if header.starts_with(b"MZ") {
+        // У настоящих PE заголовок лежит в первых сотнях байт
+        let pe_offset = u32_at(header, 0x3c).map(|offset| offset as usize);
+        return pe_offset.and_then(|offset| header.get(offset..offset.checked_add(4)?)) == Some(b"PE\0\0");
+    }
+    const MACH_O: &[[u8; 4]] = &[
+        [0xfe, 0xed, 0xfa, 0xce],
+        [0xfe, 0xed, 0xfa, 0xcf],
+        [0xce, 0xfa, 0xed, 0xfe],
+        [0xcf, 0xfa, 0xed, 0xfe],
+        // Универсальный бинарник; с таких же байт начинаются классы Java
+        [0xca, 0xfe, 0xba, 0xbe],
+    ];
+    MACH_O.iter().any(|magic| header.starts_with(magic))
+}
+
+/// Опасность по имени и первым байтам файла (без заглядывания в архивы)
+fn classify(filename: &str, header: &[u8]) -> Option<Danger> {
+    let extension = extension(filename);
+    if EXECUTABLE_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(Danger::Executable);
+    }
+    if SCRIPT_EXTENSIONS.contains(&extension.as_str()) {
+        return Some(Danger::Script);
+    }
+    if is_executable_content(header) {
+        return Some(Danger::Mismatch);
+    }
+    // Без расширения скрипт узнаём по `#!`
+    if extension.is_empty() && header.starts_with(b"#!") {
+        return Some(Danger::Script);
+    }
+    None
+}
+
+fn u16_at(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_at(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Имена файлов ZIP-архива из его центрального каталога.
+/// `None`, если каталог не найден или повреждён (в том числе у ZIP64).
+fn zip_entry_names(file: &mut (impl Read + Seek)) -> Option<Vec<String>> {
+    let len = file.seek(SeekFrom::End(0)).ok()?;
+    let tail_len = len.min((ZIP_END_LEN + u16::MAX as usize) as u64);
+    file.seek(SeekFrom::Start(len - tail_len)).ok()?;
+    let mut tail = vec![0; tail_len as usize];
+    file.read_exact(&mut tail).ok()?;
+
+    let end = (0..=tail.len().checked_sub(ZIP_END_LEN)?)
+        .rev()
+        .find(|&pos| u32_at(&tail, pos) == Some(ZIP_END_SIGNATURE))?;
+    let size = u32_at(&tail, end + 12)? as u64;
+    let offset = u32_at(&tail, end + 16)? as u64;
+    if size > MAX_CENTRAL_DIRECTORY || offset + size > len {
+        return None;
+    }
+
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut directory = vec![0; size as usize];
+    file.read_exact(&mut directory).ok()?;
+
+    let mut names = Vec::new();
+    let mut pos = 0;
+    while u32_at(&directory, pos) == Some(ZIP_CENTRAL_SIGNATURE) {
+        let name_len = u16_at(&directory, pos + 28)? as usize;
+        let extra_len = u16_at(&directory, pos + 30)? as usize;
+        let comment_len = u16_at(&directory, pos + 32)? as usize;
+        let name = directory.get(pos + 46..pos + 46 + name_len)?;
+        names.push(String::from_utf8_lossy(name).into_owned());
+        pos += 46 + name_len + extra_len + comment_len;
+    }
+    Some(names)
+}
+
+/// Снять бит исполнения, чтобы файл нельзя было запустить двойным щелчком
+#[cfg(unix)]
+fn clear_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    if mode & 0o111 != 0 {
+        permissions.set_mode(mode & !0o111);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+fn inspect_blocking(path: &Path) -> Option<Danger> {
+    #[cfg(unix)]
+    let _ = clear_executable(path);
+
+    let filename = path.file_name()?.to_string_lossy();
+    let mut file = File::open(path).ok()?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    (&mut file).take(HEADER_LEN as u64).read_to_end(&mut header).ok()?;
+
+    if let Some(danger) = classify(&filename, &header) {
+        return Some(danger);
+    }
+    if header.starts_with(ZIP_LOCAL_SIGNATURE) {
+        let names = zip_entry_names(&mut file)?;
+        if names.iter().any(|name| is_dangerous_name(name)) {
+            return Some(Danger::Archive);
+        }
+    }
+    None
+}
+
+/// Проверить скачанный файл и снять с него бит исполнения
+pub async fn inspect(path: &Path) -> Option<Danger> {
+    let path = PathBuf::from(path);
+    tokio::task::spawn_blocking(move || inspect_blocking(&path))
+        .await
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::io::Cursor;
+
+    /// ZIP из одного центрального каталога — больше разбор не читает
+    fn zip_with(names: &[&str]) -> Vec<u8> {
+        let mut data = ZIP_LOCAL_SIGNATURE.to_vec();
+        let offset = data.len() as u32;
+        for name in names {
+            data.extend_from_slice(&ZIP_CENTRAL_SIGNATURE.to_le_bytes());
+            data.extend_from_slice(&[0; 24]);
+            data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            data.extend_from_slice(&[0; 16]);
+            data.extend_from_slice(name.as_bytes());
+        }
+        let size = data.len() as u32 - offset;
+        data.extend_from_slice(&ZIP_END_SIGNATURE.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&(names.len() as u16).to_le_bytes());
+        data.extend_from_slice(&(names.len() as u16).to_le_bytes());
+        data.extend_from_slice(&size.to_le_bytes());
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&[0; 2]);
+        data
+    }
+
+    fn pe_header() -> Vec<u8> {
+        let mut header = vec![0; 0x80];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        header[0x40..0x44].copy_from_slice(b"PE\0\0");
+        header
+    }
+
+    #[test]
+    fn classifies_downloads() {
+        let cases: &[(&str, &[u8], Option<Danger>)] = &[
+            ("setup.exe", b"", Some(Danger::Executable)),
+            ("Installer.MSI", b"", Some(Danger::Executable)),
+            ("tool.AppImage", b"\x7fELF", Some(Danger::Executable)),
+            ("install.sh", b"#!/bin/sh", Some(Danger::Script)),
+            ("run.ps1", b"Write-Host", Some(Danger::Script)),
+            ("installer", b"#!/usr/bin/env bash", Some(Danger::Script)),
+            ("invoice.pdf", b"\x7fELF\x02\x01", Some(Danger::Mismatch)),
+            ("photo.jpg", &[0xcf, 0xfa, 0xed, 0xfe], Some(Danger::Mismatch)),
+            ("report.pdf", b"%PDF-1.7", None),
+            ("notes.txt", b"#!/bin/sh", None),
+            ("readme", b"MZ is a fine start for a text", None),
+        ];
+        for (name, header, expected) in cases {
+            assert_eq!(classify(name, header), *expected, "{}", name);
+        }
+        assert_eq!(classify("document.doc", &pe_header()), Some(Danger::Mismatch));
+    }
+
+    #[test]
+    fn reads_zip_entries() {
+        let zip = zip_with(&["docs/readme.txt", "bin/setup.exe"]);
+        let names = zip_entry_names(&mut Cursor::new(zip)).unwrap();
+        assert_eq!(names, ["docs/readme.txt", "bin/setup.exe"]);
+        assert!(names.iter().any(|name| is_dangerous_name(name)));
+
+        assert_eq!(zip_entry_names(&mut Cursor::new(b"PK\x03\x04 broken".to_vec())), None);
+    }
+
+    #[tokio::test]
+    async fn inspects_files() {
+        let dir = TempDir::new("danger");
+
+        let archive = dir.join("photos.zip");
+        std::fs::write(&archive, zip_with(&["a.jpg", "b.scr"])).unwrap();
+        assert_eq!(inspect(&archive).await, Some(Danger::Archive));
+
+        let photos = dir.join("clean.zip");
+        std::fs::write(&photos, zip_with(&["a.jpg", "b.png"])).unwrap();
+        assert_eq!(inspect(&photos).await, None);
+
+        let binary = dir.join("tool");
+        std::fs::write(&binary, pe_header()).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        assert_eq!(inspect(&binary).await, Some(Danger::Mismatch));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&binary).unwrap().permissions().mode() & 0o777, 0o644);
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Имя файла загрузки
+//!
+//! Разбор `Content-Disposition` по RFC 6266 (с `filename*` из RFC 5987)
+//! и очистка имени, предложенного сервером: оно не должно выводить за
+//! каталог загрузок, содержать запрещённые символы или совпадать с
+//! зарезервированными именами Windows.
+
+/// Запас до предела ФС в 255 байт — под суффикс « (1)» и временные расширения
+const MAX_FILENAME_BYTES: usize = 200;
+/// Длиннее — скорее часть имени, чем расширение; при обрезке не сохраняем
+const MAX_EXTENSION_BYTES: usize = 16;
+const FALLBACK_NAME: &str = "download";
+
+/// Зарезервированные имена устройств Windows (в любом регистре и с любым расширением)
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Расширения серверных скриптов: если сервер отдал под таким именем файл
+/// известного типа, расширение заменяем на подходящее типу
+const SCRIPT_EXTENSIONS: &[&str] = &["php", "asp", "aspx", "jsp", "cgi", "pl"];
+
+/// Расширение по MIME-типу — для имён без расширения
+const MIME_EXTENSIONS: &[(&str, &str)] = &[
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("application/gzip", "gz"),
+    ("application/x-gzip", "gz"),
+    ("application/x-tar", "tar"),
+    ("application/x-7z-compressed", "7z"),
+    ("application/vnd.rar", "rar"),
+    ("application/x-rar-compressed", "rar"),
+    ("application/json", "json"),
+    ("application/xml", "xml"),
+    ("application/javascript", "js"),
+    ("application/x-msdownload", "exe"),
+    ("application/x-msi", "msi"),
+    ("application/vnd.android.package-archive", "apk"),
+    ("application/msword", "doc"),
+    ("application/vnd.openxmlformats-officedocument.wordprocessingml.document", "docx"),
+    ("application/vnd.ms-excel", "xls"),
+    ("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet", "xlsx"),
+    ("application/vnd.openxmlformats-officedocument.presentationml.presentation", "pptx"),
+    ("text/plain", "txt"),
+    ("text/html", "html"),
+    ("text/css", "css"),
+    ("text/csv", "csv"),
+    ("text/javascript", "js"),
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/svg+xml", "svg"),
+    ("image/x-icon", "ico"),
+    ("audio/mpeg", "mp3"),
+    ("audio/ogg", "ogg"),
+    ("audio/wav", "wav"),
+    ("video/mp4", "mp4"),
+    ("video/webm", "webm"),
+];
+
+/// Значение заголовка как строка: UTF-8, а если не получилось — ISO-8859-1,
+/// как его шлют старые серверы
+pub fn header_value(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(value) => value.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Имя файла из `Content-Disposition`, как его прислал сервер (без очистки).
+/// `filename*` важнее `filename`, параметры без имени файла дают `None`.
+pub fn parse_content_disposition(header: &str) -> Option<String> {
+    let mut plain = None;
+    let mut extended = None;
+
+    for (index, param) in split_params(header).into_iter().enumerate() {
+        let Some((name, value)) = param.split_once('=') else {
+            // Первым идёт тип (`attachment`, `inline`), он нам не нужен
+            continue;
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim();
+        // Некоторые серверы опускают тип: `filename=a.txt`
+        if index == 0 && name != "filename" && name != "filename*" {
+            continue;
+        }
+        match name.as_str() {
+            "filename" if plain.is_none() => plain = Some(unquote(value)),
+            "filename*" if extended.is_none() => extended = decode_ext_value(value),
+            _ => {}
+        }
+    }
+
+    extended.or(plain).filter(|name| !name.is_empty())
+}
+
+/// Разбить заголовок по `;`, не разрывая строки в кавычках
+fn split_params(header: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (i, c) in header.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                params.push(&header[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&header[start..]);
+    params.into_iter().map(str::trim).filter(|p| !p.is_empty()).collect()
+}
+
+/// `"a \"b\""` → `a "b"`; значение без кавычек возвращается как есть
+fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"') else {
+        return value.to_string();
+    };
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            '"' => break,
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// RFC 5987: `charset'language'percent-encoded`
+fn decode_ext_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?.trim().to_ascii_lowercase();
+    let _language = parts.next()?;
+    let encoded = parts.next()?;
+    let bytes = urlencoding::decode_binary(encoded.as_bytes());
+
+    match charset.as_str() {
+        "utf-8" => String::from_utf8(bytes.into_owned()).ok(),
+        "iso-8859-1" => Some(bytes.iter().map(|&b| b as char).collect()),
+        _ => None,
+    }
+}
+
+/// Безопасное имя файла для любой ОС.
+///
+/// Остаётся только последний компонент пути; управляющие символы, символы
+/// смены направления текста и запрещённые в Windows `<>:"/\|?*` заменяются
+/// на `_`; точки и пробелы по краям убираются. Зарезервированные имена
+/// получают префикс `_`, слишком длинные обрезаются с сохранением расширения.
+/// Если расширения нет (или это расширение серверного скрипта), оно
+/// подбирается по `mime_type`.
+pub fn sanitize(name: &str, mime_type: Option<&str>) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .filter(|&c| !is_bidi_control(c))
+        .map(|c| if c.is_control() || "<>:\"/\\|?*".contains(c) { '_' } else { c })
+        .collect();
+    let mut name = cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace()).to_string();
+    if name.is_empty() {
+        name = FALLBACK_NAME.to_string();
+    }
+
+    if let Some(extension) = mime_type.and_then(extension_for_mime) {
+        match split_extension(&name) {
+            (_, None) => name = format!("{}.{}", name, extension),
+            (stem, Some(current)) if SCRIPT_EXTENSIONS.contains(&current.to_ascii_lowercase().as_str()) => {
+                name = format!("{}.{}", stem, extension);
+            }
+            _ => {}
+        }
+    }
+
+    let stem = split_extension(&name).0;
+    let device = stem.split('.').next().unwrap_or(stem).trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(device)) {
+        name = format!("_{}", name);
+    }
+
+    truncate(&name, MAX_FILENAME_BYTES)
+}
+
+/// Имя файла для загрузки: из `Content-Disposition`, иначе из пути URL
+pub fn extract_filename(url: &str, content_disposition: Option<&str>, mime_type: Option<&str>) -> String {
+    if let Some(name) = content_disposition.and_then(parse_content_disposition) {
+        return sanitize(&name, mime_type);
+    }
+
+    let from_url = url::Url::parse(url).ok().and_then(|parsed| {
+        let last = parsed.path_segments()?.next_back()?.to_string();
+        let decoded = urlencoding::decode_binary(last.as_bytes());
+        let decoded = String::from_utf8_lossy(&decoded).into_owned();
+        (!decoded.is_empty()).then_some(decoded)
+    });
+
+    match from_url {
+        Some(name) => sanitize(&name, mime_type),
+        None => sanitize(&format!("download_{}", chrono::Utc::now().timestamp()), mime_type),
+    }
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+fn extension_for_mime(mime_type: &str) -> Option<&'static str> {
+    let essence = mime_type.split(';').next()?.trim();
+    MIME_EXTENSIONS
+        .iter()
+        .find(|(mime, _)| mime.eq_ignore_ascii_case(essence))
+        .map(|(_, extension)| *extension)
+}
+
+/// `archive.tar.gz` → (`archive.tar`, `gz`)
+fn split_extension(name: &str) -> (&str, Option<&str>) {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    }
+}
+
+/// Обрезать имя до `max` байт по границе символа, сохранив короткое расширение
+fn truncate(name: &str, max: usize) -> String {
+    if name.len() <= max {
+        return name.to_string();
+    }
+    let (stem, extension) = match split_extension(name) {
+        (stem, Some(extension)) if extension.len() <= MAX_EXTENSION_BYTES => (stem, format!(".{}", extension)),
+        _ => (name, String::new()),
+    };
+    let mut end = max - extension.len();
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", stem[..end].trim_end_matches(['.', ' ']), extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_content_disposition() {
+        let cases: &[(&str, Option<&str>)] = &[
+            ("attachment; filename=report.pdf", Some("report.pdf")),
+            ("attachment; filename=\"report.pdf\"", Some("report.pdf")),
+            ("ATTACHMENT; FILENAME=\"report.pdf\"", Some("report.pdf")),
+            ("inline; filename = \"with spaces.txt\" ", Some("with spaces.txt")),
+            ("attachment; filename=\"a;b.txt\"; size=10", Some("a;b.txt")),
+            ("attachment; filename=\"say \\\"hi\\\".txt\"", Some("say \"hi\".txt")),
+            ("attachment; filename=\"back\\\\slash.txt\"", Some("back\\slash.txt")),
+            ("attachment; filename*=UTF-8''%E2%82%AC%20rates.txt", Some("€ rates.txt")),
+            ("attachment; filename*=utf-8'en'%C3%A9t%C3%A9.txt", Some("été.txt")),
+            ("attachment; filename*=ISO-8859-1''caf%E9.txt", Some("café.txt")),
+            // `filename*` важнее `filename` независимо от порядка
+            ("attachment; filename=\"fallback.txt\"; filename*=UTF-8''real.txt", Some("real.txt")),
+            ("attachment; filename*=UTF-8''real.txt; filename=\"fallback.txt\"", Some("real.txt")),
+            // Неизвестная кодировка или битый UTF-8 — берём обычный `filename`
+            ("attachment; filename*=KOI8-R''%F0%D2.txt; filename=\"plain.txt\"", Some("plain.txt")),
+            ("attachment; filename*=UTF-8''%FF.txt; filename=plain.txt", Some("plain.txt")),
+            ("filename=no-type.bin", Some("no-type.bin")),
+            ("attachment; name=\"field\"", None),
+            ("attachment", None),
+            ("attachment; filename=\"\"", None),
+            ("", None),
+        ];
+        for (header, expected) in cases {
+            assert_eq!(parse_content_disposition(header).as_deref(), *expected, "header: {:?}", header);
+        }
+    }
+
+    #[test]
+    fn decodes_latin1_header_bytes() {
+        assert_eq!(header_value(b"attachment; filename=\"caf\xe9.txt\""), "attachment; filename=\"café.txt\"");
+        assert_eq!(header_value("attachment; filename=\"été.txt\"".as_bytes()), "attachment; filename=\"été.txt\"");
+    }
+
+    #[test]
+    fn sanitizes_names() {
+        let cases: &[(&str, Option<&str>, &str)] = &[
+            ("report.pdf", None, "report.pdf"),
+            // Пути отбрасываются
+            ("../../etc/passwd", None, "passwd"),
+            ("..\\..\\Windows\\evil.dll", None, "evil.dll"),
+            ("/absolute/path.txt", None, "path.txt"),
+            ("..", None, "download"),
+            ("", None, "download"),
+            // Запрещённые и управляющие символы
+            ("a<b>c:d\"e|f?g*h.txt", None, "a_b_c_d_e_f_g_h.txt"),
+            ("line\nbreak\t.txt", None, "line_break_.txt"),
+            // Смена направления текста прячет настоящее расширение
+            ("invoice\u{202E}fdp.exe", None, "invoicefdp.exe"),
+            // Точки и пробелы по краям
+            ("  .hidden  ", None, "hidden"),
+            ("trailing. . .", None, "trailing"),
+            // Зарезервированные имена Windows
+            ("CON", None, "_CON"),
+            ("con.txt", None, "_con.txt"),
+            ("Lpt1.tar.gz", None, "_Lpt1.tar.gz"),
+            ("nul .txt", None, "_nul .txt"),
+            ("console.txt", None, "console.txt"),
+            ("COM10.txt", None, "COM10.txt"),
+            // Расширение по MIME-типу
+            ("report", Some("application/pdf"), "report.pdf"),
+            ("report", Some("Application/PDF; charset=binary"), "report.pdf"),
+            ("download.php", Some("application/zip"), "download.zip"),
+            ("notes.md", Some("text/plain"), "notes.md"),
+            ("data", Some("application/octet-stream"), "data"),
+            ("..", Some("image/png"), "download.png"),
+        ];
+        for (name, mime_type, expected) in cases {
+            assert_eq!(sanitize(name, *mime_type), *expected, "name: {:?}, mime: {:?}", name, mime_type);
+        }
+    }
+
+    #[test]
+    fn truncates_long_names() {
+        let cases: &[(String, String)] = &[
+            ("a".repeat(300) + ".pdf", "a".repeat(196) + ".pdf"),
+            // Многобайтовые символы не разрезаются
+            ("я".repeat(150) + ".txt", "я".repeat(98) + ".txt"),
+            // Слишком длинное «расширение» считается частью имени
+            ("a.".to_string() + &"b".repeat(300), "a.".to_string() + &"b".repeat(198)),
+        ];
+        for (name, expected) in cases {
+            let sanitized = sanitize(name, None);
+            assert_eq!(&sanitized, expected);
+            assert!(sanitized.len() <= MAX_FILENAME_BYTES);
+        }
+    }
+
+    #[test]
+    fn extracts_filename_from_header_or_url() {
+        let cases: &[(&str, Option<&str>, Option<&str>, &str)] = &[
+            ("https://example.com/files/a.zip", Some("attachment; filename=\"b.zip\""), None, "b.zip"),
+            ("https://example.com/files/a.zip", Some("attachment; filename=\"../b.zip\""), None, "b.zip"),
+            ("https://example.com/files/a.zip", Some("inline"), None, "a.zip"),
+            ("https://example.com/files/my%20file.tar.gz", None, None, "my file.tar.gz"),
+            ("https://example.com/files/%D1%84%D0%B0%D0%B9%D0%BB.txt", None, None, "файл.txt"),
+            ("https://example.com/files/..%2F..%2Fevil.sh", None, None, "evil.sh"),
+            ("https://example.com/get.php?id=1", None, Some("application/pdf"), "get.pdf"),
+            ("https://example.com/export", None, Some("text/csv"), "export.csv"),
+        ];
+        for (url, header, mime_type, expected) in cases {
+            assert_eq!(extract_filename(url, *header, *mime_type), *expected, "url: {:?}", url);
+        }
+
+        let fallback = extract_filename("https://example.com/", None, Some("application/zip"));
+        assert!(fallback.starts_with("download_") && fallback.ends_with(".zip"), "{}", fallback);
+    }
+}
@@ -0,0 +1,166 @@
+//! Куда сохранять загрузку
+//!
+//! Папка по умолчанию берётся из настройки `downloadPath` (пустая — системная
+//! «Загрузки»). Правила `downloadRules` направляют файлы по MIME-типу или
+//! расширению в свои папки: первое подходящее правило побеждает.
+
+use std::path::PathBuf;
+
+use crate::storage::{DownloadRule, Settings};
+
+/// Папка загрузок по умолчанию
+pub fn default_dir(settings: &Settings) -> Result<PathBuf, String> {
+    if !settings.download_path.is_empty() {
+        return Ok(PathBuf::from(&settings.download_path));
+    }
+    dirs::download_dir().ok_or_else(|| "Could not find downloads directory".to_string())
+}
+
+/// Папка для файла: из первого подходящего правила, иначе папка по умолчанию
+pub fn target_dir(settings: &Settings, filename: &str, mime_type: Option<&str>) -> Result<PathBuf, String> {
+    let routed = settings
+        .download_rules
+        .iter()
+        .filter(|rule| rule_matches(rule, filename, mime_type))
+        .find_map(|rule| expand_folder(&rule.folder));
+    match routed {
+        Some(dir) => Ok(dir),
+        None => default_dir(settings),
+    }
+}
+
+/// Папки всех правил
+pub fn rule_dirs(settings: &Settings) -> impl Iterator<Item = PathBuf> + '_ {
+    settings.download_rules.iter().filter_map(|rule| expand_folder(&rule.folder))
+}
+
+/// `~/Pictures` → `<домашний каталог>/Pictures`
+fn expand_folder(folder: &str) -> Option<PathBuf> {
+    match folder.strip_prefix('~') {
+        Some(rest) => {
+            let rest = rest.trim_start_matches(['/', '\\']);
+            dirs::home_dir().map(|home| if rest.is_empty() { home } else { home.join(rest) })
+        }
+        None => Some(PathBuf::from(folder)),
+    }
+}
+
+/// Элемент правила с `/` сравнивается с MIME-типом (`image/*` — любой
+/// подтип), без него — с расширением имени файла
+fn rule_matches(rule: &DownloadRule, filename: &str, mime_type: Option<&str>) -> bool {
+    let essence = mime_type
+        .and_then(|mime| mime.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+    let filename = filename.to_lowercase();
+
+    rule.pattern
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .any(|item| {
+            if item.contains('/') {
+                let Some(essence) = essence.as_deref() else {
+                    return false;
+                };
+                match item.strip_suffix("/*") {
+                    Some(kind) => essence.split('/').next() == Some(kind),
+                    None => essence == item,
+                }
+            } else {
+                let extension = item.trim_start_matches('*').trim_start_matches('.');
+                !extension.is_empty() && filename.ends_with(&format!(".{}", extension))
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, folder: &str) -> DownloadRule {
+        DownloadRule {
+            pattern: pattern.to_string(),
+            folder: folder.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_rules() {
+        let cases: &[(&str, &str, Option<&str>, bool)] = &[
+            // MIME-тип целиком и любой подтип
+            ("application/pdf", "a.bin", Some("application/pdf"), true),
+            ("application/pdf", "a.bin", Some("Application/PDF; charset=binary"), true),
+            ("image/*", "photo", Some("image/png"), true),
+            ("image/*", "photo.png", Some("video/mp4"), false),
+            ("image/*", "photo.png", None, false),
+            // Расширение с точкой, маской или без них
+            ("pdf", "Report.PDF", None, true),
+            (".pdf", "report.pdf", None, true),
+            ("*.pdf", "report.pdf", Some("application/octet-stream"), true),
+            ("*.pdf", "report.pdf.exe", None, false),
+            ("pdf", "pdf", None, false),
+            (".tar.gz", "backup.tar.gz", None, true),
+            // Список через запятую
+            ("zip, rar ,7z", "archive.rar", None, true),
+            ("zip, image/*", "photo", Some("image/jpeg"), true),
+            ("zip, rar", "archive.tar", None, false),
+            (" , ,", "file.txt", None, false),
+            ("*", "file.txt", None, false),
+        ];
+        for (pattern, filename, mime_type, expected) in cases {
+            assert_eq!(
+                rule_matches(&rule(pattern, "/tmp"), filename, *mime_type),
+                *expected,
+                "pattern: {:?}, file: {:?}, mime: {:?}",
+                pattern,
+                filename,
+                mime_type
+            );
+        }
+    }
+
+    #[test]
+    fn expands_home_folder() {
+        let home = dirs::home_dir().unwrap();
+        let cases: &[(&str, PathBuf)] = &[
+            ("~", home.clone()),
+            ("~/", home.clone()),
+            ("~/Pictures", home.join("Pictures")),
+            ("~\\Pictures", home.join("Pictures")),
+            ("/srv/files", PathBuf::from("/srv/files")),
+            ("relative/~", PathBuf::from("relative/~")),
+        ];
+        for (folder, expected) in cases {
+            assert_eq!(expand_folder(folder).as_ref(), Some(expected), "folder: {:?}", folder);
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let settings = Settings {
+            download_path: "/downloads".to_string(),
+            download_rules: vec![
+                rule("application/pdf", "/docs"),
+                rule("pdf, image/*", "/mixed"),
+                rule("png", "/pictures"),
+            ],
+            ..Default::default()
+        };
+        let cases: &[(&str, Option<&str>, &str)] = &[
+            ("report.pdf", Some("application/pdf"), "/docs"),
+            ("report.pdf", None, "/mixed"),
+            ("photo.png", Some("image/png"), "/mixed"),
+            ("photo.png", None, "/pictures"),
+            ("notes.txt", Some("text/plain"), "/downloads"),
+        ];
+        for (filename, mime_type, expected) in cases {
+            assert_eq!(
+                target_dir(&settings, filename, *mime_type).unwrap(),
+                PathBuf::from(expected),
+                "file: {:?}, mime: {:?}",
+                filename,
+                mime_type
+            );
+        }
+    }
+}
@@ -0,0 +1,1466 @@
+//! Загрузки файлов
+//!
+//! Список загрузок хранится в `downloads.json` каталога профиля; сама передача
+//! по HTTP — в `transfer.rs`. Пока загрузка не завершена, данные лежат
+//! в `<имя>.axiondownload` (см. `partial.rs`).
+
+mod checksum;
+mod danger;
+mod filename;
+mod location;
+mod partial;
+mod progress;
+mod provenance;
+mod retry;
+mod segmented;
+mod space;
+mod throttle;
+mod transfer;
+
+use serde::{Deserialize, Serialize};
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE, REFERER};
+use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use tokio::sync::watch;
+
+use crate::storage;
+use checksum::{Checksum, HashAlgorithm};
+use danger::Danger;
+use retry::TransferError;
+use throttle::{RateLimiter, Throttle};
+use progress::SpeedMeter;
+use provenance::{Provenance, RedirectLog};
+use space::SpaceGuard;
+use transfer::{Control, Progress, SegmentProgress, TransferEnd, TransferOutcome, Validator};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Download {
+    pub id: String,
+    pub filename: String,
+    pub url: String,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: i64,
+    #[serde(rename = "receivedBytes")]
+    pub received_bytes: i64,
+    pub state: String,
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+    #[serde(rename = "savePath")]
+    pub save_path: Option<String>,
+    #[serde(rename = "speed", default)]
+    pub speed: i64,
+    #[serde(rename = "mimeType", default)]
+    pub mime_type: Option<String>,
+    /// ETag и Last-Modified ответа — для проверки при докачке
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(rename = "lastModified", default)]
+    pub last_modified: Option<String>,
+    /// Сервер принимает `Range` — файл можно качать в несколько соединений
+    #[serde(rename = "acceptRanges", default)]
+    pub accept_ranges: bool,
+    /// Свой лимит скорости, байт/с (заменяет общий; 0 — без ограничений)
+    #[serde(rename = "speedLimit", default)]
+    pub speed_limit: Option<u64>,
+    /// Номер повторной попытки после временной ошибки (0 — повторов не было)
+    #[serde(rename = "retryAttempt", default)]
+    pub retry_attempt: u32,
+    /// Когда начнётся следующая попытка (мс), пока загрузка в состоянии `retrying`
+    #[serde(rename = "nextRetryAt", default)]
+    pub next_retry_at: Option<i64>,
+    /// Последняя ошибка передачи
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Ожидаемая контрольная сумма (`sha256:<hex>`); при несовпадении
+    /// загрузка получает состояние `verification_failed`
+    #[serde(rename = "expectedDigest", default)]
+    pub expected_digest: Option<String>,
+    /// Контрольная сумма скачанного файла (`sha256:<hex>`)
+    #[serde(default)]
+    pub digest: Option<String>,
+    /// Сколько загрузка шла, мс (без пауз и ожидания в очереди)
+    #[serde(rename = "elapsedMs", default)]
+    pub elapsed_ms: i64,
+    /// Чем опасен готовый файл: `executable`, `script`, `archive` или `mismatch`.
+    /// Пока пользователь не подтвердит загрузку (`needs_confirmation`), её нельзя открыть
+    #[serde(default)]
+    pub danger: Option<String>,
+    /// Откуда взялась загрузка: вкладка, referer, перенаправления, ответ сервера
+    #[serde(default)]
+    pub provenance: Provenance,
+}
+
+/// Событие `download-progress` идущей загрузки
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub id: String,
+    #[serde(rename = "receivedBytes")]
+    pub received_bytes: i64,
+    /// -1, если размер неизвестен
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: i64,
+    /// Сглаженная скорость, байт/с
+    pub speed: i64,
+    /// Сколько секунд осталось; `None`, если размер неизвестен или загрузка стоит
+    #[serde(rename = "etaSeconds")]
+    pub eta_seconds: Option<i64>,
+    /// Сколько загрузка уже идёт, мс (без пауз и ожидания в очереди)
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: i64,
+    /// Сегменты при загрузке в несколько соединений (пусто для одного потока)
+    pub segments: Vec<SegmentProgress>,
+}
+
+/// Менеджер активных загрузок
+pub struct DownloadManager {
+    /// Каналы управления (пауза/отмена) идущими загрузками
+    pub controls: HashMap<String, watch::Sender<Control>>,
+    /// Ожидающие загрузки в порядке запуска
+    pub queue: VecDeque<String>,
+    /// Общий лимит скорости из настроек
+    pub global_limiter: Arc<RateLimiter>,
+    /// Собственные лимиты идущих загрузок
+    pub limiters: HashMap<String, Arc<RateLimiter>>,
+    /// Загрузки, оборванные закрытием приложения; фронтенд забирает их один раз,
+    /// чтобы предложить продолжить
+    pub interrupted_on_startup: Vec<Download>,
+    /// Заголовки запроса (cookie и referer страницы) для загрузок из WebView.
+    /// Хранятся только в памяти, чтобы не записывать cookie на диск
+    pub request_headers: HashMap<String, HeaderMap>,
+    /// Загрузки `blob:` и `data:`, которые сохраняет сам WebView
+    pub native: Vec<Download>,
+}
+
+impl DownloadManager {
+    pub fn new() -> Self {
+        Self {
+            controls: HashMap::new(),
+            queue: VecDeque::new(),
+            global_limiter: Arc::new(RateLimiter::new(None)),
+            limiters: HashMap::new(),
+            interrupted_on_startup: Vec::new(),
+            request_headers: HashMap::new(),
+            native: Vec::new(),
+        }
+    }
+
+    /// Поставить загрузку в конец очереди
+    pub fn enqueue(&mut self, id: &str) {
+        if !self.queue.iter().any(|queued| queued == id) {
+            self.queue.push_back(id.to_string());
+        }
+    }
+
+    /// Убрать загрузку из очереди. `false`, если её там не было
+    pub fn dequeue(&mut self, id: &str) -> bool {
+        match self.queue.iter().position(|queued| queued == id) {
+            Some(index) => {
+                self.queue.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Переместить загрузку на позицию в очереди (0 — запустится следующей)
+    pub fn move_in_queue(&mut self, id: &str, position: usize) -> Result<(), String> {
+        if !self.dequeue(id) {
+            return Err(format!("Download {} is not queued", id));
+        }
+        let position = position.min(self.queue.len());
+        self.queue.insert(position, id.to_string());
+        Ok(())
+    }
+
+    /// Взять из очереди следующую загрузку, если есть свободный слот,
+    /// и сразу занять слот её каналом управления
+    fn next_to_start(&mut self, max_concurrent: usize) -> Option<(String, watch::Receiver<Control>)> {
+        if self.controls.len() >= max_concurrent {
+            return None;
+        }
+        let id = self.queue.pop_front()?;
+        let (control_tx, control_rx) = watch::channel(Control::Run);
+        self.controls.insert(id.clone(), control_tx);
+        Some((id, control_rx))
+    }
+}
+
+/// Папка загрузок по умолчанию (из настроек или системная «Загрузки»)
+pub async fn default_downloads_dir() -> Result<PathBuf, String> {
+    let settings = storage::get_settings().await.unwrap_or_default();
+    location::default_dir(&settings)
+}
+
+/// Спросить, куда сохранить файл. `None` — пользователь отказался.
+/// Ответ диалога ждём через канал, не занимая поток tokio
+async fn ask_save_path(app: &AppHandle, dir: &Path, filename: &str) -> Option<PathBuf> {
+    use tauri_plugin_dialog::DialogExt;
+    
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_directory(dir)
+        .set_file_name(filename)
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    rx.await
+        .ok()
+        .flatten()
+        .and_then(|p| p.as_path().map(Path::to_path_buf))
+}
+
+fn get_downloads_file() -> Result<PathBuf, String> {
+    Ok(storage::ensure_data_dir()?.join("downloads.json"))
+}
+
+pub async fn get_downloads() -> Result<Vec<Download>, String> {
+    let path = get_downloads_file()?;
+    
+    Ok(storage::read_json(&path).await?.unwrap_or_default())
+}
+
+/// Изменения `downloads.json` идут строго по очереди: параллельные
+/// «прочитать → изменить → записать» иначе затирают друг друга
+static DOWNLOADS_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Записать список загрузок; вызывающий держит [`DOWNLOADS_LOCK`]
+async fn save_downloads(downloads: Vec<Download>) -> Result<(), String> {
+    let path = get_downloads_file()?;
+    
+    storage::write_json(&path, &downloads).await
+}
+
+/// Добавить загрузку в начало истории
+async fn insert_download(download: Download) -> Result<(), String> {
+    let _guard = DOWNLOADS_LOCK.lock().await;
+    let mut downloads = get_downloads().await?;
+    downloads.insert(0, download);
+    save_downloads(downloads).await
+}
+
+/// Изменить запись о загрузке в истории и вернуть её обновлённую копию
+async fn update_download(
+    id: &str,
+    update: impl FnOnce(&mut Download),
+) -> Result<Option<Download>, String> {
+    let _guard = DOWNLOADS_LOCK.lock().await;
+    let mut downloads = get_downloads().await?;
+    let updated = match downloads.iter_mut().find(|d| d.id == id) {
+        Some(dl) => {
+            update(dl);
+            dl.clone()
+        }
+        None => return Ok(None),
+    };
+    save_downloads(downloads).await?;
+    Ok(Some(updated))
+}
+
+/// Загрузка завершилась: опасный файл ждёт подтверждения пользователя
+fn mark_finished(dl: &mut Download, danger: Option<Danger>) {
+    dl.danger = danger.map(|danger| danger.as_str().to_string());
+    match danger {
+        Some(danger) => {
+            dl.state = "needs_confirmation".to_string();
+            dl.error = Some(danger.description().to_string());
+        }
+        None => {
+            dl.state = "completed".to_string();
+            dl.error = None;
+        }
+    }
+}
+
+/// Привести список загрузок в порядок при запуске.
+///
+/// Записи `progressing` и `retrying` остались от прошлого запуска, который закрылся посреди
+/// передачи: помечаем их прерванными и берём число полученных байт из частичного
+/// файла — с этого места загрузка продолжится. Если файл уже скачан целиком,
+/// а запись не успела обновиться, он получает итоговое имя и загрузка считается завершённой.
+/// Ожидающие в очереди записи не трогаем — их вернёт в очередь [`restore`].
+/// Возвращает загрузки, которые стали прерванными.
+pub async fn reconcile_downloads() -> Result<Vec<Download>, String> {
+    let _guard = DOWNLOADS_LOCK.lock().await;
+    let mut downloads = get_downloads().await?;
+    let mut interrupted = Vec::new();
+    let mut changed = false;
+    
+    for dl in downloads.iter_mut() {
+        if !is_resumable(dl) {
+            continue;
+        }
+        let save_path = dl.save_path.as_deref().map(PathBuf::from);
+        if let Some(path) = &save_path {
+            partial::adopt_legacy(path).await;
+        }
+        if dl.state != "progressing" && dl.state != "retrying" && dl.state != "paused" {
+            continue;
+        }
+        
+        // Файл сегментной загрузки выделен целиком — прогресс берём из карты сегментов
+        let (on_disk, segmented) = match save_path.as_deref().map(partial::part_path) {
+            Some(part) => match segmented::saved_progress(&part).await {
+                Some(received) => (received, true),
+                None => (tokio::fs::metadata(&part).await.map(|m| m.len() as i64).unwrap_or(0), false),
+            },
+            None => (0, false),
+        };
+        changed = true;
+        dl.speed = 0;
+        dl.received_bytes = on_disk;
+        
+        let finished = match &save_path {
+            Some(path) if !segmented && dl.total_bytes > 0 && on_disk == dl.total_bytes => {
+                match partial::finalize(path).await {
+                    Ok(()) => Some(danger::inspect(path).await),
+                    Err(_) => None,
+                }
+            }
+            _ => None,
+        };
+        if let Some(danger) = finished {
+            mark_finished(dl, danger);
+        } else if dl.state == "progressing" || dl.state == "retrying" {
+            dl.state = "interrupted".to_string();
+            dl.next_retry_at = None;
+            interrupted.push(dl.clone());
+        }
+    }
+    
+    if changed {
+        save_downloads(downloads).await?;
+    }
+    Ok(interrupted)
+}
+
+/// Загрузку ещё можно продолжить — её недокачанный файл нужен
+fn is_resumable(download: &Download) -> bool {
+    matches!(
+        download.state.as_str(),
+        "queued" | "too_large" | "progressing" | "retrying" | "paused" | "interrupted" | "insufficient_space"
+    )
+}
+
+/// Удалить при запуске брошенные `.axiondownload`: от завершённых и отменённых
+/// загрузок истории, а также давно не менявшиеся без записи в истории.
+/// Смотрим папку загрузок, папки правил и папки загрузок из истории.
+pub async fn remove_stale_parts() -> Result<usize, String> {
+    let settings = storage::get_settings().await.unwrap_or_default();
+    let downloads = get_downloads().await?;
+    
+    let mut dirs: HashSet<PathBuf> = downloads.iter()
+        .filter_map(|dl| dl.save_path.as_deref())
+        .filter_map(|path| Path::new(path).parent().map(Path::to_path_buf))
+        .collect();
+    dirs.extend(location::default_dir(&settings).ok());
+    dirs.extend(location::rule_dirs(&settings));
+    
+    let (resumable, finished): (Vec<_>, Vec<_>) = downloads.iter().partition(|dl| is_resumable(dl));
+    let paths = |list: Vec<&Download>| -> HashSet<PathBuf> {
+        list.into_iter()
+            .filter_map(|dl| dl.save_path.as_deref().map(PathBuf::from))
+            .collect()
+    };
+    Ok(partial::remove_stale(&dirs, &paths(resumable), &paths(finished)).await)
+}
+
+/// Забрать загрузки, прерванные прошлым запуском (отдаются один раз)
+pub fn take_interrupted_on_startup(app: &AppHandle) -> Result<Vec<Download>, String> {
+    let state = app.state::<crate::AppState>();
+    let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+    Ok(std::mem::take(&mut manager.interrupted_on_startup))
+}
+
+/// Восстановить менеджер при запуске: общий лимит скорости из настроек
+/// и очередь загрузок, ожидавших запуска при закрытии приложения
+pub async fn restore(app: &AppHandle) -> Result<(), String> {
+    let settings = storage::get_settings().await.unwrap_or_default();
+    set_global_speed_limit(app, settings.download_speed_limit)?;
+    
+
+    let mut queued: Vec<Download> = get_downloads().await?
+        .into_iter()
+        .filter(|d| d.state == "queued")
+        .collect();
+    queued.sort_by_key(|d| d.start_time);
+    
+    {
+        let state = app.state::<crate::AppState>();
+        let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        for dl in &queued {
+            manager.enqueue(&dl.id);
+        }
+    }
+    
+    schedule(app).await
+}
+
+/// Следить за настройками загрузок: новый лимит скорости применяется к идущим
+/// загрузкам, а при увеличении числа одновременных загрузок сразу стартуют ожидающие
+pub fn watch_settings(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen("settings-changed", move |event| {
+        let change: storage::SettingsChanged = match serde_json::from_str(event.payload()) {
+            Ok(change) => change,
+            Err(_) => return,
+        };
+        if change.changed.iter().any(|key| key == "downloadSpeedLimit") {
+            let _ = set_global_speed_limit(&handle, change.settings.download_speed_limit);
+        }
+        if change.changed.iter().any(|key| key == "maxConcurrentDownloads") {
+            let handle = handle.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = schedule(&handle).await;
+            });
+        }
+    });
+}
+
+/// Продолжить все прерванные загрузки, вернуть число поставленных в очередь
+pub async fn resume_interrupted_downloads(app: &AppHandle) -> Result<usize, String> {
+    let mut resumed = 0;
+    let mut interrupted: Vec<Download> = get_downloads().await?
+        .into_iter()
+        .filter(|d| d.state == "interrupted")
+        .collect();
+    interrupted.sort_by_key(|d| d.start_time);
+    for dl in interrupted {
+        if resume_download_by_id(app, &dl.id).await.is_ok() {
+            resumed += 1;
+        }
+    }
+    Ok(resumed)
+}
+
+pub async fn clear_completed() -> Result<(), String> {
+    let path = get_downloads_file()?;
+    
+    // Читаем текущие загрузки и оставляем только активные и ждущие подтверждения
+    let _guard = DOWNLOADS_LOCK.lock().await;
+    let downloads = get_downloads().await.unwrap_or_default();
+    let (active, cleared): (Vec<Download>, Vec<Download>) = downloads.into_iter()
+        .partition(|d| matches!(d.state.as_str(), "queued" | "too_large" | "progressing" | "retrying" | "paused" | "needs_confirmation"));
+    
+    if active.is_empty() {
+        storage::remove_json(&path).await?;
+    } else {
+        save_downloads(active).await?;
+    }
+    
+    // Прерванную загрузку после очистки уже не продолжить — удаляем
+    // частичный файл и заглушку имени
+    for dl in cleared {
+        if matches!(dl.state.as_str(), "interrupted" | "insufficient_space") {
+            if let Some(path) = dl.save_path.as_deref() {
+                partial::discard(Path::new(path)).await;
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Начать загрузку файла. С `expected_digest` (`sha256:<hex>`, `sha1:…`,
+/// `md5:…` или просто hex) готовый файл сверяется с контрольной суммой.
+pub async fn start_download(
+    app: AppHandle,
+    url: String,
+    suggested_filename: Option<String>,
+    expected_digest: Option<String>,
+) -> Result<Download, String> {
+    let expected_digest = expected_digest
+        .filter(|digest| !digest.trim().is_empty())
+        .map(|digest| Checksum::parse(&digest))
+        .transpose()?;
+    start_download_with_headers(app, url, suggested_filename, expected_digest, HeaderMap::new(), Provenance::default()).await
+}
+
+/// Загрузка, начатая страницей в WebView. Её скачивает менеджер, а не сам
+/// WebView, поэтому запросы повторяют cookie и referer страницы — иначе
+/// файлы за авторизацией не скачались бы.
+pub async fn start_webview_download(
+    app: AppHandle,
+    webview: tauri::Webview,
+    tab_id: String,
+    url: String,
+    suggested_filename: Option<String>,
+) -> Result<Download, String> {
+    let mut provenance = webview_provenance(&webview, tab_id);
+    let mut headers = HeaderMap::new();
+    if let Ok(parsed) = url::Url::parse(&url) {
+        // cookies_for_url нельзя вызывать из обработчика событий WebView (зависает
+        // на Windows), поэтому эта функция вызывается в отдельной задаче
+        let cookies = webview.cookies_for_url(parsed).unwrap_or_default();
+        if !cookies.is_empty() {
+            let cookie = cookies.iter()
+                .map(|c| format!("{}={}", c.name(), c.value()))
+                .collect::<Vec<_>>()
+                .join("; ");
+            if let Ok(value) = HeaderValue::from_str(&cookie) {
+                headers.insert(COOKIE, value);
+            }
+        }
+    }
+    if let Ok(page) = webview.url() {
+        if matches!(page.scheme(), "http" | "https") {
+            if let Ok(value) = HeaderValue::from_str(page.as_str()) {
+                headers.insert(REFERER, value);
+                provenance.referrer = Some(page.to_string());
+            }
+        }
+    }
+    
+    start_download_with_headers(app, url, suggested_filename, None, headers, provenance).await
+}
+
+/// Вкладка и страница, с которых WebView начал загрузку
+fn webview_provenance(webview: &tauri::Webview, tab_id: String) -> Provenance {
+    Provenance {
+        tab_id: Some(tab_id),
+        page_url: webview.url().ok().map(|page| page.to_string()),
+        ..Default::default()
+    }
+}
+
+async fn start_download_with_headers(
+    app: AppHandle,
+    url: String,
+    suggested_filename: Option<String>,
+    expected_digest: Option<Checksum>,
+    headers: HeaderMap,
+    mut provenance: Provenance,
+) -> Result<Download, String> {
+    let download_id = format!("dl_{}", uuid::Uuid::new_v4().to_string().replace("-", "")[..12].to_string());
+    let settings = storage::get_settings().await.unwrap_or_default();
+    
+    // Клиент запоминает перенаправления — они попадут в историю загрузки
+    let redirects = RedirectLog::default();
+    let client = client_builder(headers.clone())
+        .redirect(redirects.policy())
+        .build()
+        .map_err(|e| e.to_string())?;
+    
+    // Делаем HEAD запрос для получения информации
+    let head_response = client.head(&url).send().await;
+    
+    let (total_bytes, content_disposition, mime_type, accept_ranges, validator) = match head_response {
+        Ok(resp) => {
+            provenance.record_response(&resp, &redirects);
+            let headers = resp.headers();
+            let header = |name: &str| headers.get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            let size = header("content-length")
+                .and_then(|s| s.parse::<i64>().ok())
+                .unwrap_or(-1);
+            // Имя в Content-Disposition бывает не в ASCII
+            let cd = headers.get("content-disposition").map(|v| filename::header_value(v.as_bytes()));
+            let mime = header("content-type")
+                .map(|s| s.split(';').next().unwrap_or(&s).trim().to_string());
+            let accept_ranges = header("accept-ranges")
+                .map(|s| s.split(',').any(|unit| unit.trim().eq_ignore_ascii_case("bytes")))
+                .unwrap_or(false);
+            let validator = Validator {
+                etag: header("etag"),
+                last_modified: header("last-modified"),
+            };
+            (size, cd, mime, accept_ranges, validator)
+        }
+        Err(_) => (-1, None, None, false, Validator::default()),
+    };
+    
+    // Определяем имя файла
+    let filename = match suggested_filename {
+        Some(name) => filename::sanitize(&name, mime_type.as_deref()),
+        None => filename::extract_filename(&url, content_disposition.as_deref(), mime_type.as_deref()),
+    };
+    let downloads_dir = location::target_dir(&settings, &filename, mime_type.as_deref())?;
+    tokio::fs::create_dir_all(&downloads_dir).await.map_err(|e| e.to_string())?;
+    
+    // Имя занимаем сразу, чтобы одновременная загрузка не выбрала его же.
+    // В диалоге пользователь сам подтверждает перезапись существующего файла
+    let save_path = if settings.ask_where_to_save {
+        let path = ask_save_path(&app, &downloads_dir, &filename).await
+            .ok_or_else(|| "Download cancelled".to_string())?;
+        partial::reserve_exact(&path)?;
+        path
+    } else {
+        partial::reserve(&downloads_dir, &filename)?
+    };
+    let unique_filename = save_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(filename);
+    
+    // Файл больше порога из настроек начнёт скачиваться только после подтверждения
+    let too_large = settings.download_confirm_size > 0 && total_bytes > settings.download_confirm_size as i64;
+    
+    let download = Download {
+        id: download_id.clone(),
+        filename: unique_filename.clone(),
+        url: url.clone(),
+        total_bytes,
+        received_bytes: 0,
+        state: if too_large { "too_large" } else { "queued" }.to_string(),
+        start_time: chrono::Utc::now().timestamp_millis(),
+        save_path: Some(save_path.to_string_lossy().to_string()),
+        speed: 0,
+        mime_type,
+        etag: validator.etag,
+        last_modified: validator.last_modified,
+        accept_ranges,
+        expected_digest: expected_digest.map(|digest| digest.to_string()),
+        provenance,
+        ..Default::default()
+    };
+    
+    // Отправляем начальное событие
+    let _ = app.emit("download-started", &download);
+    let _ = app.emit("download-update", &download);
+    
+    // Сохраняем в историю
+    let _ = insert_download(download.clone()).await;
+    
+    // Ставим в очередь — запустится, когда освободится слот
+    {
+        let state = app.state::<crate::AppState>();
+        let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        if !headers.is_empty() {
+            manager.request_headers.insert(download.id.clone(), headers);
+        }
+        if !too_large {
+            manager.enqueue(&download.id);
+        }
+    }
+    schedule(&app).await?;
+    
+    Ok(download)
+}
+
+/// Начать загрузку `blob:` или `data:`: такие адреса доступны только самой
+/// странице, поэтому файл сохраняет WebView. Возвращает id загрузки и путь
+/// сохранения — с тем же выбором имени и папки, что и у остальных загрузок. Диалог
+/// «куда сохранить» здесь не показываем: обработчик загрузок WebView
+/// вызывается в потоке окна и не может ждать диалог.
+pub fn begin_native_download(
+    app: &AppHandle,
+    webview: &tauri::Webview,
+    tab_id: String,
+    url: &str,
+    suggested_filename: Option<String>,
+) -> Result<(String, PathBuf), String> {
+    let settings = tauri::async_runtime::block_on(storage::get_settings()).unwrap_or_default();
+    let filename = match suggested_filename {
+        Some(name) => filename::sanitize(&name, None),
+        None => filename::extract_filename(url, None, None),
+    };
+    let downloads_dir = location::target_dir(&settings, &filename, None)?;
+    std::fs::create_dir_all(&downloads_dir).map_err(|e| e.to_string())?;
+    let save_path = partial::reserve(&downloads_dir, &filename)?;
+    let unique_filename = save_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or(filename);
+    
+    let download = Download {
+        id: format!("dl_{}", uuid::Uuid::new_v4().to_string().replace("-", "")[..12].to_string()),
+        filename: unique_filename,
+        url: url.to_string(),
+        total_bytes: -1,
+        state: "progressing".to_string(),
+        start_time: chrono::Utc::now().timestamp_millis(),
+        save_path: Some(save_path.to_string_lossy().to_string()),
+        provenance: webview_provenance(webview, tab_id),
+        ..Default::default()
+    };
+    let _ = app.emit("download-started", &download);
+    let _ = app.emit("download-update", &download);
+    
+    let id = download.id.clone();
+    let state = app.state::<crate::AppState>();
+    let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+    manager.native.push(download);
+    Ok((id, save_path))
+}
+
+/// WebView закончил сохранять файл, начатый [`begin_native_download`].
+/// Загрузку узнаём только по занятому для неё пути; если WebView путь не
+/// сообщил, загрузка `id` считается прерванной.
+/// Загрузки, отданные менеджеру, сюда тоже попадают (отменёнными) — их пропускаем.
+pub fn finish_native_download(app: &AppHandle, id: Option<&str>, path: Option<&Path>, success: bool) {
+    let download = {
+        let state = app.state::<crate::AppState>();
+        let Ok(mut manager) = state.download_manager.lock() else {
+            return;
+        };
+        let index = manager.native.iter().position(|dl| match path {
+            Some(path) => dl.save_path.as_deref().map(Path::new) == Some(path),
+            None => Some(dl.id.as_str()) == id,
+        });
+        match index {
+            Some(index) => manager.native.remove(index),
+            None => return,
+        }
+    };
+    
+    let mut dl = download;
+    let saved = success && path.is_some();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let path = dl.save_path.clone().map(PathBuf::from);
+        match path {
+            Some(path) if saved => {
+                mark_finished(&mut dl, danger::inspect(&path).await);
+                if let Ok(meta) = tokio::fs::metadata(&path).await {
+                    dl.total_bytes = meta.len() as i64;
+                    dl.received_bytes = meta.len() as i64;
+                }
+            }
+            path => {
+                // Заглушка имени больше не нужна
+                if let Some(path) = path {
+                    partial::discard(&path).await;
+                }
+                dl.state = "interrupted".to_string();
+            }
+        }
+        let _ = app.emit("download-update", &dl);
+        let _ = app.emit("download-completed", &dl);
+        
+        let _ = insert_download(dl).await;
+    });
+}
+
+fn client_builder(headers: HeaderMap) -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(crate::webview_manager::CHROME_USER_AGENT)
+        .default_headers(headers)
+}
+
+/// Текущий порядок очереди
+pub fn queue_snapshot(app: &AppHandle) -> Result<Vec<String>, String> {
+    let state = app.state::<crate::AppState>();
+    let manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+    Ok(manager.queue.iter().cloned().collect())
+}
+
+fn emit_queue(app: &AppHandle) {
+    if let Ok(queue) = queue_snapshot(app) {
+        let _ = app.emit("download-queue-changed", &queue);
+    }
+}
+
+/// Запускать загрузки из очереди, пока есть свободные слоты
+pub async fn schedule(app: &AppHandle) -> Result<(), String> {
+    let max_concurrent = storage::get_settings().await
+        .unwrap_or_default()
+        .max_concurrent_downloads as usize;
+    
+    loop {
+        let next = {
+            let state = app.state::<crate::AppState>();
+            let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+            manager.next_to_start(max_concurrent)
+        };
+        let (id, control_rx) = match next {
+            Some(next) => next,
+            None => break,
+        };
+        
+        match update_download(&id, |dl| dl.state = "progressing".to_string()).await {
+            Ok(Some(download)) => {
+                let _ = app.emit("download-update", &download);
+                spawn_transfer(app.clone(), download, control_rx);
+            }
+            // Запись удалили, пока загрузка ждала, — освобождаем слот
+            other => {
+                let state = app.state::<crate::AppState>();
+                if let Ok(mut manager) = state.download_manager.lock() {
+                    manager.controls.remove(&id);
+                };
+                other?;
+            }
+        }
+    }
+    
+    emit_queue(app);
+    Ok(())
+}
+
+/// Событие прогресса загрузки. Одно на весь запуск передачи, чтобы
+/// сглаженная скорость не сбрасывалась при повторах. Заодно следит за местом
+/// на диске в папке `dir` и останавливает передачу, если оно кончается.
+/// Если размер не был известен заранее, при первом ответе с размером
+/// проверяет порог `confirm_size` и место под весь файл.
+fn progress_emitter(
+    app: &AppHandle,
+    download: &Download,
+    dir: &Path,
+    confirm_size: u64,
+) -> impl FnMut(Progress) -> Result<(), TransferError> {
+    let app = app.clone();
+    let id = download.id.clone();
+    let elapsed_before = download.elapsed_ms;
+    let started = Instant::now();
+    let mut meter = SpeedMeter::new(download.received_bytes);
+    let mut space = SpaceGuard::new(dir);
+    let dir = dir.to_path_buf();
+    let mut size_checked = download.total_bytes > 0;
+    move |progress| {
+        if !size_checked && progress.total_bytes > 0 {
+            size_checked = true;
+            if confirm_size > 0 && progress.total_bytes > confirm_size as i64 {
+                return Err(TransferError::too_large(progress.total_bytes, confirm_size));
+            }
+            space::check(&dir, (progress.total_bytes - progress.received_bytes).max(0) as u64)?;
+        }
+        
+        let speed = meter.update(progress.received_bytes);
+        let update = DownloadProgress {
+            id: id.clone(),
+            received_bytes: progress.received_bytes,
+            total_bytes: progress.total_bytes,
+            speed,
+            eta_seconds: progress::eta_seconds(progress.received_bytes, progress.total_bytes, speed),
+            elapsed_ms: elapsed_before + started.elapsed().as_millis() as i64,
+            segments: progress.segments,
+        };
+        let _ = app.emit("download-progress", &update);
+        space.poll()
+    }
+}
+
+/// Скачать файл в недокачанный `part_path`: большой — в несколько соединений,
+/// если сервер поддерживает диапазоны, иначе одним потоком
+async fn run_transfer(
+    client: &reqwest::Client,
+    download: &Download,
+    part_path: &Path,
+    validator: &Validator,
+    control: &mut watch::Receiver<Control>,
+    throttle: &Throttle,
+    on_progress: &mut impl FnMut(Progress) -> Result<(), TransferError>,
+) -> Result<TransferOutcome, TransferError> {
+    let connections = storage::get_settings().await
+        .unwrap_or_default()
+        .download_connections;
+    let algorithm = digest_algorithm(download);
+    
+    // Продолжаем сегментную загрузку или начинаем новую; частично скачанный
+    // одним потоком файл докачивается так же, одним потоком
+    let segmented = segmented::saved_progress(part_path).await.is_some()
+        || (download.received_bytes == 0
+            && segmented::should_split(download.accept_ranges, download.total_bytes, connections, validator));
+    if segmented {
+        let outcome = segmented::transfer(
+            client,
+            &download.url,
+            part_path,
+            download.total_bytes,
+            validator,
+            connections,
+            control,
+            throttle,
+            &mut *on_progress,
+        ).await?;
+        if let Some(mut outcome) = outcome {
+            if outcome.end == TransferEnd::Completed {
+                let digest = checksum::hash_file(part_path, algorithm).await
+                    .map_err(TransferError::permanent)?;
+                outcome.digest = Some(digest.to_string());
+            }
+            return Ok(outcome);
+        }
+    }
+    
+    transfer::transfer(
+        client,
+        &download.url,
+        part_path,
+        download.total_bytes,
+        validator,
+        control,
+        throttle,
+        Some(algorithm),
+        on_progress,
+    ).await
+}
+
+/// Алгоритм контрольной суммы: как у ожидаемой, иначе SHA-256
+fn digest_algorithm(download: &Download) -> HashAlgorithm {
+    download.expected_digest.as_deref()
+        .and_then(|digest| Checksum::parse(digest).ok())
+        .map(|digest| digest.algorithm)
+        .unwrap_or(HashAlgorithm::Sha256)
+}
+
+/// Сколько байт загрузки уже на диске (по карте сегментов или по размеру файла)
+async fn bytes_on_disk(path: &Path) -> Option<i64> {
+    match segmented::saved_progress(path).await {
+        Some(received) => Some(received),
+        None => tokio::fs::metadata(path).await.ok().map(|m| m.len() as i64),
+    }
+}
+
+/// Подождать перед повтором. Если за это время пришла пауза или отмена,
+/// вернуть, чем закончилась загрузка.
+async fn wait_for_retry(delay: Duration, control: &mut watch::Receiver<Control>) -> Option<TransferEnd> {
+    let sleep = tokio::time::sleep(delay);
+    tokio::pin!(sleep);
+    loop {
+        let command = *control.borrow();
+        match command {
+            Control::Run => {}
+            Control::Pause => return Some(TransferEnd::Paused),
+            Control::Cancel => return Some(TransferEnd::Cancelled),
+        }
+        tokio::select! {
+            _ = &mut sleep => return None,
+            changed = control.changed() => {
+                if changed.is_err() {
+                    (&mut sleep).await;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Скачать файл, повторяя временные ошибки с нарастающей задержкой.
+/// Пока загрузка ждёт повтора, её состояние — `retrying`; попытка, которая
+/// продвинула загрузку, сбрасывает счётчик.
+async fn run_with_retries(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    download: &Download,
+    part_path: &Path,
+    validator: &Validator,
+    control: &mut watch::Receiver<Control>,
+    throttle: &Throttle,
+) -> Result<TransferOutcome, TransferError> {
+    let mut attempt = 0;
+    let mut offset = bytes_on_disk(part_path).await.unwrap_or(0);
+    let dir = part_path.parent().unwrap_or(Path::new("."));
+    let confirm_size = storage::get_settings().await
+        .unwrap_or_default()
+        .download_confirm_size;
+    let mut on_progress = progress_emitter(app, download, dir, confirm_size);
+    
+    // Остаток файла должен поместиться на диск. Если размер неизвестен,
+    // проверим, когда его сообщит сервер
+    if download.total_bytes > 0 {
+        space::check(dir, (download.total_bytes - offset).max(0) as u64)?;
+    }
+    
+    loop {
+        let error = match run_transfer(client, download, part_path, validator, control, throttle, &mut on_progress).await {
+            Err(e) if e.transient => e,
+            result => return result,
+        };
+        
+        let received = bytes_on_disk(part_path).await.unwrap_or(0);
+        if received > offset {
+            attempt = 0;
+            offset = received;
+        }
+        attempt += 1;
+        if attempt > retry::MAX_RETRIES {
+            return Err(error);
+        }
+        
+        let delay = retry::backoff(attempt, error.retry_after);
+        let retry_at = chrono::Utc::now().timestamp_millis() + delay.as_millis() as i64;
+        let retrying = update_download(&download.id, |dl| {
+            dl.state = "retrying".to_string();
+            dl.speed = 0;
+            dl.received_bytes = received;
+            dl.retry_attempt = attempt;
+            dl.next_retry_at = Some(retry_at);
+            dl.error = Some(error.message.clone());
+        }).await;
+        if let Ok(Some(dl)) = retrying {
+            let _ = app.emit("download-update", &dl);
+        }
+        
+        if let Some(end) = wait_for_retry(delay, control).await {
+            return Ok(TransferOutcome {
+                end,
+                received_bytes: received,
+                total_bytes: download.total_bytes,
+                validator: validator.clone(),
+                digest: None,
+                response: None,
+            });
+        }
+        
+        let resumed = update_download(&download.id, |dl| {
+            dl.state = "progressing".to_string();
+            dl.next_retry_at = None;
+        }).await;
+        if let Ok(Some(dl)) = resumed {
+            let _ = app.emit("download-update", &dl);
+        }
+    }
+}
+
+/// Запустить (или продолжить) передачу файла в фоне. Слот в менеджере
+/// уже занят каналом управления `control_rx`.
+fn spawn_transfer(app: AppHandle, download: Download, mut control_rx: watch::Receiver<Control>) {
+    let (throttle, headers) = {
+        let own = Arc::new(RateLimiter::new(download.speed_limit));
+        let state = app.state::<crate::AppState>();
+        let (global, headers) = match state.download_manager.lock() {
+            Ok(mut manager) => {
+                manager.limiters.insert(download.id.clone(), own.clone());
+                let headers = manager.request_headers.get(&download.id).cloned().unwrap_or_default();
+                (manager.global_limiter.clone(), headers)
+            }
+            Err(_) => (Arc::new(RateLimiter::new(None)), HeaderMap::new()),
+        };
+        (Throttle::new(global, own), headers)
+    };
+    
+    tokio::spawn(async move {
+        // Без пути передача завершится ошибкой и загрузка станет прерванной
+        let save_path = PathBuf::from(download.save_path.clone().unwrap_or_default());
+        let part_path = partial::part_path(&save_path);
+        let started = Instant::now();
+        let validator = Validator {
+            etag: download.etag.clone(),
+            last_modified: download.last_modified.clone(),
+        };
+        
+        // Перенаправления запроса, с которого начнётся передача, попадут в историю
+        let redirects = RedirectLog::default();
+        let client = client_builder(headers).redirect(redirects.policy()).build();
+        let mut result = match client {
+            Ok(client) => run_with_retries(&app, &client, &download, &part_path, &validator, &mut control_rx, &throttle).await,
+            Err(e) => Err(TransferError::permanent(e.to_string())),
+        };
+        
+        // Готовый файл получает итоговое имя и проверяется, от отменённого не остаётся ничего
+        let mut danger = None;
+        match &result {
+            Ok(outcome) if outcome.end == TransferEnd::Completed => {
+                match partial::finalize(&save_path).await {
+                    Ok(()) => danger = danger::inspect(&save_path).await,
+                    Err(e) => result = Err(TransferError::io(e)),
+                }
+            }
+            Ok(outcome) if outcome.end == TransferEnd::Cancelled => partial::discard(&save_path).await,
+            _ => {}
+        }
+        
+        // Удаляем из активных загрузок
+        {
+            let state = app.state::<crate::AppState>();
+            if let Ok(mut manager) = state.download_manager.lock() {
+                manager.controls.remove(&download.id);
+                manager.limiters.remove(&download.id);
+                // Заголовки нужны, пока загрузку можно продолжить
+                let finished = matches!(&result, Ok(outcome) if outcome.end != TransferEnd::Paused);
+                if finished {
+                    manager.request_headers.remove(&download.id);
+                }
+            };
+        }
+        
+        // Сколько байт уже на диске — для прерванной загрузки
+        let partial_bytes = bytes_on_disk(&part_path).await;
+        
+        // Обновляем в истории
+        let updated = update_download(&download.id, |dl| {
+            dl.speed = 0;
+            dl.next_retry_at = None;
+            dl.elapsed_ms += started.elapsed().as_millis() as i64;
+            match &result {
+                Ok(outcome) => {
+                    dl.state = match outcome.end {
+                        TransferEnd::Completed => "completed",
+                        TransferEnd::Paused => "paused",
+                        TransferEnd::Cancelled => "cancelled",
+                    }.to_string();
+                    dl.received_bytes = outcome.received_bytes;
+                    dl.total_bytes = outcome.total_bytes;
+                    dl.etag = outcome.validator.etag.clone();
+                    dl.last_modified = outcome.validator.last_modified.clone();
+                    dl.retry_attempt = 0;
+                    dl.error = None;
+                    if outcome.digest.is_some() {
+                        dl.digest = outcome.digest.clone();
+                    }
+                    // Происхождение — по ответу, который на самом деле отдал файл
+                    if let Some(response) = outcome.response.clone() {
+                        dl.provenance.record(response, &redirects);
+                    }
+                    // Готовый файл сверяем с ожидаемой контрольной суммой
+                    if outcome.end == TransferEnd::Completed {
+                        mark_finished(dl, danger);
+                        if let Some(expected) = dl.expected_digest.clone() {
+                            if dl.digest.as_deref() != Some(expected.as_str()) {
+                                dl.state = "verification_failed".to_string();
+                                dl.error = Some(format!(
+                                    "Checksum mismatch: expected {}, got {}",
+                                    expected,
+                                    dl.digest.as_deref().unwrap_or("none")
+                                ));
+                            }
+                        }
+                    }
+                }
+                // Размер стал известен только из ответа — ждём подтверждения
+                Err(TransferError { too_large: Some(total_bytes), .. }) => {
+                    dl.state = "too_large".to_string();
+                    dl.total_bytes = *total_bytes;
+                    if let Some(received) = partial_bytes {
+                        dl.received_bytes = received;
+                    }
+                }
+                Err(e) => {
+                    // Частичный файл остаётся — загрузку можно возобновить
+                    dl.state = if e.insufficient_space { "insufficient_space" } else { "interrupted" }.to_string();
+                    dl.error = Some(e.message.clone());
+                    if let Some(received) = partial_bytes {
+                        dl.received_bytes = received;
+                    }
+                }
+            }
+        }).await;
+        
+        if let Ok(Some(dl)) = updated {
+            let _ = app.emit("download-update", &dl);
+            if !matches!(dl.state.as_str(), "paused" | "too_large") {
+                let _ = app.emit("download-completed", &dl);
+            }
+        }
+        
+        // Слот освободился — запускаем следующую из очереди
+        let _ = schedule(&app).await;
+    });
+}
+
+/// Посчитать контрольную сумму скачанного файла. Если у загрузки есть
+/// ожидаемая сумма того же алгоритма, состояние обновляется по результату сверки.
+pub async fn compute_download_hash(app: &AppHandle, id: &str, algorithm: &str) -> Result<String, String> {
+    let algorithm = HashAlgorithm::parse(algorithm)?;
+    let download = get_downloads().await?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| format!("Download {} not found", id))?;
+    if !matches!(download.state.as_str(), "completed" | "needs_confirmation" | "verification_failed") {
+        return Err(format!("Download {} is not finished", id));
+    }
+    let path = PathBuf::from(download.save_path.ok_or_else(|| format!("Download {} has no file", id))?);
+    let digest = checksum::hash_file(&path, algorithm).await?.to_string();
+    let danger = danger::inspect(&path).await;
+    
+    let updated = update_download(id, |dl| {
+        dl.digest = Some(digest.clone());
+        let expected = dl.expected_digest.as_deref().and_then(|e| Checksum::parse(e).ok());
+        if let Some(expected) = expected.filter(|e| e.algorithm == algorithm) {
+            if expected.to_string() == digest {
+                // Подтверждённую или ждущую подтверждения загрузку не трогаем
+                if dl.state == "verification_failed" {
+                    mark_finished(dl, danger);
+                }
+            } else {
+                dl.state = "verification_failed".to_string();
+                dl.error = Some(format!("Checksum mismatch: expected {}, got {}", expected, digest));
+            }
+        }
+    }).await?;
+    if let Some(dl) = updated {
+        let _ = app.emit("download-update", &dl);
+    }
+    
+    Ok(digest)
+}
+
+fn send_control(app: &AppHandle, id: &str, control: Control) -> Result<bool, String> {
+    let state = app.state::<crate::AppState>();
+    let manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+    match manager.controls.get(id) {
+        Some(tx) => {
+            let _ = tx.send(control);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Убрать загрузку из очереди. `false`, если она не ждала запуска
+fn remove_from_queue(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let removed = {
+        let state = app.state::<crate::AppState>();
+        let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        manager.dequeue(id)
+    };
+    if removed {
+        emit_queue(app);
+    }
+    Ok(removed)
+}
+
+/// Поставить загрузку на паузу (частичный файл сохраняется).
+/// Ожидающая загрузка просто убирается из очереди.
+pub async fn pause_download_by_id(app: &AppHandle, id: &str) -> Result<(), String> {
+    if send_control(app, id, Control::Pause)? {
+        return Ok(());
+    }
+    if !remove_from_queue(app, id)? {
+        return Err(format!("Download {} is not running", id));
+    }
+    if let Some(dl) = update_download(id, |dl| dl.state = "paused".to_string()).await? {
+        let _ = app.emit("download-update", &dl);
+    }
+    Ok(())
+}
+
+/// Продолжить приостановленную или прерванную загрузку
+pub async fn resume_download_by_id(app: &AppHandle, id: &str) -> Result<(), String> {
+    let download = get_downloads().await?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| format!("Download {} not found", id))?;
+    
+    if !matches!(download.state.as_str(), "paused" | "interrupted" | "insufficient_space") {
+        return Err(format!("Download {} cannot be resumed", id));
+    }
+    
+    let download = update_download(id, |dl| dl.state = "queued".to_string())
+        .await?
+        .unwrap_or(download);
+    let _ = app.emit("download-update", &download);
+    
+    {
+        let state = app.state::<crate::AppState>();
+        let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        manager.enqueue(id);
+    }
+    schedule(app).await
+}
+
+/// Лимиты меньше 1 КБ/с бессмысленны — такая загрузка фактически стоит
+fn check_speed_limit(limit: u64) -> Result<(), String> {
+    if limit != 0 && limit < 1024 {
+        return Err(format!("Speed limit must be 0 (unlimited) or at least 1024 bytes/s, got {}", limit));
+    }
+    Ok(())
+}
+
+fn set_global_speed_limit(app: &AppHandle, limit: u64) -> Result<(), String> {
+    let state = app.state::<crate::AppState>();
+    let manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+    manager.global_limiter.set_limit(Some(limit));
+    Ok(())
+}
+
+/// Задать загрузке свой лимит скорости (`None` — снова действует общий).
+/// Идущая загрузка подхватывает его сразу.
+pub async fn set_speed_limit_by_id(app: &AppHandle, id: &str, limit: Option<u64>) -> Result<(), String> {
+    if let Some(limit) = limit {
+        check_speed_limit(limit)?;
+    }
+    
+    let updated = update_download(id, |dl| dl.speed_limit = limit)
+        .await?
+        .ok_or_else(|| format!("Download {} not found", id))?;
+    
+    {
+        let state = app.state::<crate::AppState>();
+        let manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        if let Some(limiter) = manager.limiters.get(id) {
+            limiter.set_limit(limit);
+        }
+    }
+    
+    let _ = app.emit("download-update", &updated);
+    Ok(())
+}
+
+/// Переместить ожидающую загрузку на позицию в очереди
+pub fn move_in_queue(app: &AppHandle, id: &str, position: usize) -> Result<(), String> {
+    {
+        let state = app.state::<crate::AppState>();
+        let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        manager.move_in_queue(id, position)?;
+    }
+    emit_queue(app);
+    Ok(())
+}
+
+/// Отменить загрузку
+pub async fn cancel_download_by_id(app: &AppHandle, id: &str) -> Result<(), String> {
+    // Идущая загрузка сама удалит частичный файл
+    let running = send_control(app, id, Control::Cancel)?;
+    remove_from_queue(app, id)?;
+    if !running {
+        let state = app.state::<crate::AppState>();
+        let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+        manager.request_headers.remove(id);
+    }
+    
+    // Обновляем статус в истории
+    let mut previous_state = String::new();
+    let updated = update_download(id, |dl| {
+        previous_state = std::mem::replace(&mut dl.state, "cancelled".to_string());
+    }).await?;
+    if let Some(dl) = updated {
+        // У загрузки, которая сейчас не идёт, частичный файл и заглушку имени удаляем сами
+        let pending = matches!(
+            previous_state.as_str(),
+            "queued" | "too_large" | "paused" | "interrupted" | "insufficient_space"
+        );
+        if !running && pending {
+            if let Some(path) = dl.save_path.as_deref() {
+                partial::discard(Path::new(path)).await;
+            }
+        }
+        // Опасный файл, который пользователь не стал сохранять, удаляем
+        if previous_state == "needs_confirmation" {
+            if let Some(path) = dl.save_path.as_deref() {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+        }
+        let _ = app.emit("download-update", &dl);
+    }
+    
+    Ok(())
+}
+
+/// Подтвердить загрузку: опасный файл после этого можно открыть,
+/// а слишком большой встаёт в очередь
+pub async fn confirm_download_by_id(app: &AppHandle, id: &str) -> Result<(), String> {
+    let mut previous_state = String::new();
+    let updated = update_download(id, |dl| {
+        previous_state = dl.state.clone();
+        match dl.state.as_str() {
+            "needs_confirmation" => {
+                dl.state = "completed".to_string();
+                dl.error = None;
+            }
+            "too_large" => dl.state = "queued".to_string(),
+            _ => {}
+        }
+    }).await?
+        .ok_or_else(|| format!("Download {} not found", id))?;
+    
+    match previous_state.as_str() {
+        "needs_confirmation" => {
+            let _ = app.emit("download-update", &updated);
+            Ok(())
+        }
+        "too_large" => {
+            let _ = app.emit("download-update", &updated);
+            {
+                let state = app.state::<crate::AppState>();
+                let mut manager = state.download_manager.lock().map_err(|e| e.to_string())?;
+                manager.enqueue(id);
+            }
+            schedule(app).await
+        }
+        _ => Err(format!("Download {} does not need confirmation", id)),
+    }
+}
+
+/// Путь к файлу загрузки, который можно открыть: только завершённой
+/// и, если файл опасный, подтверждённой пользователем
+pub async fn openable_path(id: &str) -> Result<PathBuf, String> {
+    let download = get_downloads().await?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| format!("Download {} not found", id))?;
+    match download.state.as_str() {
+        "completed" => {}
+        "needs_confirmation" => return Err(format!("Download {} needs confirmation", id)),
+        _ => return Err(format!("Download {} is not completed", id)),
+    }
+    download.save_path
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("Download {} has no file", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(manager: &DownloadManager) -> Vec<&str> {
+        manager.queue.iter().map(String::as_str).collect()
+    }
+
+    /// Запустить все загрузки, для которых есть слот
+    fn start_all(manager: &mut DownloadManager, max_concurrent: usize) -> Vec<String> {
+        std::iter::from_fn(|| manager.next_to_start(max_concurrent).map(|(id, _)| id)).collect()
+    }
+
+    #[test]
+    fn starts_in_fifo_order() {
+        let mut manager = DownloadManager::new();
+        for id in ["a", "b", "c", "a"] {
+            manager.enqueue(id);
+        }
+        // Повторная постановка не дублирует загрузку
+        assert_eq!(queued(&manager), ["a", "b", "c"]);
+
+        assert_eq!(start_all(&mut manager, 10), ["a", "b", "c"]);
+        assert!(manager.queue.is_empty());
+    }
+
+    #[test]
+    fn respects_concurrency_cap() {
+        let mut manager = DownloadManager::new();
+        for id in ["a", "b", "c", "d"] {
+            manager.enqueue(id);
+        }
+
+        assert_eq!(start_all(&mut manager, 2), ["a", "b"]);
+        assert_eq!(queued(&manager), ["c", "d"]);
+
+        // Слот освободился — запускается следующая
+        manager.controls.remove("a");
+        assert_eq!(start_all(&mut manager, 2), ["c"]);
+        // Лимит уменьшили ниже числа идущих — новые не запускаются
+        assert_eq!(start_all(&mut manager, 1), Vec::<String>::new());
+        assert_eq!(queued(&manager), ["d"]);
+    }
+
+    #[test]
+    fn moves_within_queue() {
+        let mut manager = DownloadManager::new();
+        for id in ["a", "b", "c", "d"] {
+            manager.enqueue(id);
+        }
+
+        // Поднять в начало — запустится следующей
+        manager.move_in_queue("c", 0).unwrap();
+        assert_eq!(queued(&manager), ["c", "a", "b", "d"]);
+        manager.move_in_queue("c", 2).unwrap();
+        assert_eq!(queued(&manager), ["a", "b", "c", "d"]);
+        // Позиция за концом — в конец очереди
+        manager.move_in_queue("a", 100).unwrap();
+        assert_eq!(queued(&manager), ["b", "c", "d", "a"]);
+
+        assert!(manager.move_in_queue("missing", 0).is_err());
+        assert_eq!(start_all(&mut manager, 1), ["b"]);
+    }
+
+    #[test]
+    fn dequeue_leaves_running_downloads() {
+        let mut manager = DownloadManager::new();
+        for id in ["a", "b"] {
+            manager.enqueue(id);
+        }
+        assert_eq!(start_all(&mut manager, 1), ["a"]);
+
+        // Идущей загрузки в очереди нет — её останавливают через канал управления
+        assert!(!manager.dequeue("a"));
+        assert!(manager.controls.contains_key("a"));
+        assert!(manager.move_in_queue("a", 0).is_err());
+
+        assert!(manager.dequeue("b"));
+        assert!(!manager.dequeue("b"));
+        assert!(manager.queue.is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+//! Недокачанные файлы
+//!
+//! Пока загрузка идёт, данные пишутся в `<имя>.axiondownload` рядом с
+//! итоговым файлом и переименовываются в него только после успешного
+//! завершения — недокачанный файл не выглядит готовым. Итоговое имя сразу
+//! занимается пустым файлом-заглушкой, поэтому одновременные загрузки файлов
+//! с одинаковым именем не выберут одно и то же имя.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::segmented;
+
+pub const PART_EXTENSION: &str = "axiondownload";
+
+/// Недокачанный файл без записи в истории (например, другого профиля)
+/// удаляем только после недели без изменений
+const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// `file.zip` → `file.zip.axiondownload`
+pub fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(PART_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// `file.zip.axiondownload` → `file.zip`
+fn final_path(part: &Path) -> Option<PathBuf> {
+    (part.extension() == Some(OsStr::new(PART_EXTENSION))).then(|| part.with_extension(""))
+}
+
+/// Имена-кандидаты: `file.zip`, `file (1).zip`, `file (2).zip`…
+fn candidates(filename: &str) -> impl Iterator<Item = String> + '_ {
+    let path = Path::new(filename);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(filename);
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|e| format!(".{}", e))
+        .unwrap_or_default();
+
+    (0..=1000).map(move |i| match i {
+        0 => filename.to_string(),
+        1000 => format!("{}_{}{}", stem, chrono::Utc::now().timestamp(), ext),
+        i => format!("{} ({}){}", stem, i, ext),
+    })
+}
+
+/// Занять свободное имя в папке пустой заглушкой. Файл создаётся только
+/// если его ещё нет, поэтому два одновременных вызова получат разные имена.
+pub fn reserve(dir: &Path, filename: &str) -> Result<PathBuf, String> {
+    for name in candidates(filename) {
+        let path = dir.join(&name);
+        if part_path(&path).exists() {
+            continue;
+        }
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Err(format!("No free file name for {} in {}", filename, dir.display()))
+}
+
+/// Занять путь, выбранный в диалоге. Существующий файл пользователь уже
+/// согласился перезаписать — он заменится только готовой загрузкой.
+pub fn reserve_exact(path: &Path) -> Result<(), String> {
+    match std::fs::OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Переименовать скачанный файл в итоговое имя, заменив заглушку
+pub async fn finalize(path: &Path) -> std::io::Result<()> {
+    tokio::fs::rename(part_path(path), path).await
+}
+
+/// Удалить недокачанный файл, его карту сегментов и заглушку итогового имени.
+/// Непустой итоговый файл не трогаем — это файл, выбранный для перезаписи.
+pub async fn discard(path: &Path) {
+    segmented::discard(&part_path(path)).await;
+    if let Ok(meta) = tokio::fs::metadata(path).await {
+        if meta.is_file() && meta.len() == 0 {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+    }
+}
+
+/// Недокачанный файл от загрузки, начатой до появления `.axiondownload`:
+/// данные лежали прямо в итоговом файле. Переносим их, чтобы докачать.
+pub async fn adopt_legacy(path: &Path) {
+    let part = part_path(path);
+    if tokio::fs::try_exists(&part).await.unwrap_or(true) {
+        return;
+    }
+    let has_data = tokio::fs::metadata(path).await.map(|m| m.len() > 0).unwrap_or(false);
+    if has_data && segmented::rename(path, &part).await.is_ok() {
+        let _ = reserve_exact(path);
+    }
+}
+
+/// Удалить брошенные недокачанные файлы в `dirs`. Файлы из `keep` (загрузки,
+/// которые ещё можно продолжить) остаются; файлы из `known` (завершённые
+/// и отменённые загрузки истории) удаляются сразу, остальные — если давно
+/// не менялись. Возвращает число удалённых.
+pub async fn remove_stale(dirs: &HashSet<PathBuf>, keep: &HashSet<PathBuf>, known: &HashSet<PathBuf>) -> usize {
+    let mut removed = 0;
+    for dir in dirs {
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let part = entry.path();
+            let Some(path) = final_path(&part) else {
+                continue;
+            };
+            if keep.contains(&path) {
+                continue;
+            }
+            let stale = known.contains(&path)
+                || entry
+                    .metadata()
+                    .await
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                    .is_some_and(|age| age > STALE_AFTER);
+            if stale {
+                discard(&path).await;
+                removed += 1;
+            }
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn maps_part_paths() {
+        let part = part_path(Path::new("/tmp/file.tar.gz"));
+        assert_eq!(part, PathBuf::from("/tmp/file.tar.gz.axiondownload"));
+        assert_eq!(final_path(&part), Some(PathBuf::from("/tmp/file.tar.gz")));
+        assert_eq!(final_path(Path::new("/tmp/file.tar.gz")), None);
+    }
+
+    #[test]
+    fn reserves_distinct_names() {
+        let dir = TempDir::new("partial");
+        std::fs::write(dir.join("file.zip"), b"done").unwrap();
+        std::fs::write(dir.join("file (1).zip.axiondownload"), b"part").unwrap();
+
+        let first = reserve(&dir, "file.zip").unwrap();
+        let second = reserve(&dir, "file.zip").unwrap();
+        assert_eq!(first, dir.join("file (2).zip"));
+        assert_eq!(second, dir.join("file (3).zip"));
+        assert_eq!(std::fs::metadata(&first).unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn finalizes_and_discards() {
+        let dir = TempDir::new("partial");
+        let path = reserve(&dir, "report.pdf").unwrap();
+        tokio::fs::write(part_path(&path), b"content").await.unwrap();
+        finalize(&path).await.unwrap();
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"content");
+        assert!(!part_path(&path).exists());
+
+        let other = reserve(&dir, "report.pdf").unwrap();
+        tokio::fs::write(part_path(&other), b"half").await.unwrap();
+        discard(&other).await;
+        assert!(!other.exists() && !part_path(&other).exists());
+        // Готовый файл с тем же именем остаётся
+        discard(&path).await;
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn removes_only_stale_parts() {
+        let dir = TempDir::new("partial");
+        let resumable = dir.join("resumable.iso");
+        let cancelled = dir.join("cancelled.iso");
+        let unknown = dir.join("unknown.iso");
+        for path in [&resumable, &cancelled, &unknown] {
+            reserve_exact(path).unwrap();
+            std::fs::write(part_path(path), b"part").unwrap();
+        }
+
+        let dirs = HashSet::from([dir.to_path_buf()]);
+        let keep = HashSet::from([resumable.clone()]);
+        let known = HashSet::from([cancelled.clone(), resumable.clone()]);
+        assert_eq!(remove_stale(&dirs, &keep, &known).await, 1);
+        assert!(part_path(&resumable).exists());
+        assert!(!part_path(&cancelled).exists() && !cancelled.exists());
+        // Свежий файл без записи в истории мог оставить другой профиль
+        assert!(part_path(&unknown).exists());
+    }
+}
@@ -0,0 +1,121 @@
+//! Скорость и оставшееся время загрузки
+//!
+//! Скорость — экспоненциальное скользящее среднее по отсчётам прогресса.
+//! Вес отсчёта зависит от прошедшего времени, поэтому неровные интервалы
+//! между событиями не искажают среднее, а скачки сглаживаются примерно
+//! за [`SMOOTHING`].
+
+use std::time::{Duration, Instant};
+
+/// Постоянная времени сглаживания скорости
+const SMOOTHING: Duration = Duration::from_secs(3);
+
+pub struct SpeedMeter {
+    last_at: Instant,
+    last_bytes: i64,
+    /// Байт в секунду; `None`, пока не было ни одного отсчёта
+    speed: Option<f64>,
+}
+
+impl SpeedMeter {
+    /// Начать отсчёт с `received_bytes` уже скачанных байт
+    pub fn new(received_bytes: i64) -> Self {
+        Self {
+            last_at: Instant::now(),
+            last_bytes: received_bytes,
+            speed: None,
+        }
+    }
+
+    /// Учесть новый отсчёт и вернуть сглаженную скорость, байт/с
+    pub fn update(&mut self, received_bytes: i64) -> i64 {
+        self.update_at(Instant::now(), received_bytes)
+    }
+
+    fn update_at(&mut self, now: Instant, received_bytes: i64) -> i64 {
+        let elapsed = now.duration_since(self.last_at).as_secs_f64();
+        if elapsed > 0.0 {
+            let sample = (received_bytes - self.last_bytes).max(0) as f64 / elapsed;
+            let alpha = 1.0 - (-elapsed / SMOOTHING.as_secs_f64()).exp();
+            self.speed = Some(match self.speed {
+                Some(speed) => speed + alpha * (sample - speed),
+                None => sample,
+            });
+            self.last_at = now;
+            self.last_bytes = received_bytes;
+        }
+        self.speed.unwrap_or(0.0).round() as i64
+    }
+}
+
+/// Сколько секунд осталось; `None`, если размер неизвестен или загрузка стоит
+pub fn eta_seconds(received_bytes: i64, total_bytes: i64, speed: i64) -> Option<i64> {
+    if total_bytes <= 0 || speed <= 0 {
+        return None;
+    }
+    let remaining = (total_bytes - received_bytes).max(0);
+    Some((remaining as f64 / speed as f64).ceil() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meter_at(start: Instant) -> SpeedMeter {
+        SpeedMeter {
+            last_at: start,
+            last_bytes: 0,
+            speed: None,
+        }
+    }
+
+    #[test]
+    fn smooths_speed_spikes() {
+        let start = Instant::now();
+        let mut meter = meter_at(start);
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Первый отсчёт берётся как есть
+        assert_eq!(meter.update_at(at(250), 250), 1000);
+        assert_eq!(meter.update_at(at(500), 500), 1000);
+        // Кратковременный скачок в 10 раз почти не двигает среднее
+        let spiked = meter.update_at(at(750), 3000);
+        assert!(spiked > 1000 && spiked < 2000, "{}", spiked);
+        // При ровной скорости среднее к ней сходится
+        let mut bytes = 3000;
+        let mut speed = 0;
+        for step in 4..200 {
+            bytes += 500;
+            speed = meter.update_at(at(step * 250), bytes);
+        }
+        assert_eq!(speed, 2000);
+    }
+
+    #[test]
+    fn weights_samples_by_interval() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Один длинный интервал и четыре коротких с той же скоростью дают одно и то же
+        let mut long = meter_at(start);
+        long.update_at(at(1000), 1000);
+        let long = long.update_at(at(2000), 5000);
+
+        let mut short = meter_at(start);
+        short.update_at(at(1000), 1000);
+        let mut short_speed = 0;
+        for step in 1..=4 {
+            short_speed = short.update_at(at(1000 + step * 250), 1000 + step as i64 * 1000);
+        }
+        assert!((long - short_speed).abs() <= 1, "{} vs {}", long, short_speed);
+    }
+
+    #[test]
+    fn estimates_remaining_time() {
+        assert_eq!(eta_seconds(500, 1500, 100), Some(10));
+        assert_eq!(eta_seconds(500, 1550, 100), Some(11));
+        assert_eq!(eta_seconds(1500, 1500, 100), Some(0));
+        assert_eq!(eta_seconds(500, -1, 100), None);
+        assert_eq!(eta_seconds(500, 1500, 0), None);
+    }
+}
@@ -0,0 +1,154 @@
+//! Происхождение загрузки
+//!
+//! Для аудита и повторной загрузки с того же зеркала запоминаем вкладку и
+//! страницу, с которых началась загрузка, referer, цепочку перенаправлений,
+//! итоговый адрес и заголовки ответа сервера.
+
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use reqwest::redirect::Policy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Столько же перенаправлений допускает reqwest по умолчанию
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Вкладка, с которой началась загрузка
+    #[serde(rename = "tabId", default)]
+    pub tab_id: Option<String>,
+    /// Страница, открытая во вкладке в момент загрузки
+    #[serde(rename = "pageUrl", default)]
+    pub page_url: Option<String>,
+    #[serde(default)]
+    pub referrer: Option<String>,
+    /// Адреса, которые сервер перенаправил дальше, начиная с исходного
+    /// (пусто, если перенаправлений не было)
+    #[serde(default)]
+    pub redirects: Vec<String>,
+    /// Адрес, с которого сервер на самом деле отдал файл
+    #[serde(rename = "finalUrl", default)]
+    pub final_url: Option<String>,
+    /// Заголовки ответа; повторяющиеся склеены через `, `
+    #[serde(rename = "responseHeaders", default)]
+    pub response_headers: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    /// Запомнить ответ сервера и пройденные перенаправления
+    pub fn record_response(&mut self, response: &reqwest::Response, redirects: &RedirectLog) {
+        self.record(ResponseRecord::of(response), redirects);
+    }
+
+    /// Запомнить сохранённый ответ сервера и пройденные перенаправления
+    pub fn record(&mut self, response: ResponseRecord, redirects: &RedirectLog) {
+        self.redirects = redirects.take();
+        self.final_url = Some(response.url);
+        self.response_headers = response.headers;
+    }
+}
+
+/// Адрес и заголовки ответа, с которого началась передача файла
+#[derive(Debug, Clone, Default)]
+pub struct ResponseRecord {
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+}
+
+impl ResponseRecord {
+    pub fn of(response: &reqwest::Response) -> Self {
+        Self {
+            url: response.url().to_string(),
+            headers: collect_headers(response.headers()),
+        }
+    }
+}
+
+/// Заголовки ответа без `Set-Cookie` — сессию в истории загрузок не храним
+fn collect_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut collected = BTreeMap::<String, String>::new();
+    for (name, value) in headers {
+        if name == SET_COOKIE {
+            continue;
+        }
+        let value = String::from_utf8_lossy(value.as_bytes());
+        collected
+            .entry(name.as_str().to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    collected
+}
+
+/// Цепочка перенаправлений, которую запоминает политика клиента
+#[derive(Clone, Default)]
+pub struct RedirectLog(Arc<Mutex<Vec<String>>>);
+
+impl RedirectLog {
+    /// Политика перенаправлений, как у reqwest по умолчанию, но с записью адресов
+    pub fn policy(&self) -> Policy {
+        let log = self.0.clone();
+        Policy::custom(move |attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            if let Ok(mut log) = log.lock() {
+                *log = attempt.previous().iter().map(|url| url.to_string()).collect();
+            }
+            attempt.follow()
+        })
+    }
+
+    fn take(&self) -> Vec<String> {
+        self.0.lock().map(|mut log| std::mem::take(&mut *log)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve, Reply};
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn collects_headers_without_cookies() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/zip"));
+        headers.append("link", HeaderValue::from_static("<https://a.example/f.zip>; rel=duplicate"));
+        headers.append("link", HeaderValue::from_static("<https://b.example/f.zip>; rel=duplicate"));
+        headers.insert(SET_COOKIE, HeaderValue::from_static("session=secret"));
+
+        let collected = collect_headers(&headers);
+        assert_eq!(collected.len(), 2);
+        assert_eq!(collected["content-type"], "application/zip");
+        assert_eq!(
+            collected["link"],
+            "<https://a.example/f.zip>; rel=duplicate, <https://b.example/f.zip>; rel=duplicate"
+        );
+    }
+
+    #[tokio::test]
+    async fn records_redirect_chain() {
+        // /start → /mirror → /file.zip
+        let server = serve(|request, _| match request.path.as_str() {
+            "/start" => Reply::new("302 Found").header("Location", "/mirror"),
+            "/mirror" => Reply::new("302 Found").header("Location", "/file.zip"),
+            _ => Reply::new("200 OK").header("ETag", "\"v1\""),
+        })
+        .await;
+
+        let redirects = RedirectLog::default();
+        let client = reqwest::Client::builder().redirect(redirects.policy()).build().unwrap();
+        let response = client.head(server.url("/start")).send().await.unwrap();
+
+        let mut provenance = Provenance::default();
+        provenance.record_response(&response, &redirects);
+        assert_eq!(provenance.redirects, vec![server.url("/start"), server.url("/mirror")]);
+        assert_eq!(provenance.final_url, Some(server.url("/file.zip")));
+        assert_eq!(provenance.response_headers["etag"], "\"v1\"");
+    }
+}
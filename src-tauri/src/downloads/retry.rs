@@ -0,0 +1,262 @@
+//! Ошибки передачи и повторные попытки
+//!
+//! Временные ошибки (обрыв или таймаут соединения, 5xx, 408, 429) повторяются
+//! с экспоненциальной задержкой со случайным разбросом, чтобы загрузки не
+//! переподключались к серверу одновременно. Если сервер прислал `Retry-After`,
+//! ждём не меньше указанного. Остальные ошибки (4xx, нет места на диске)
+//! сразу останавливают загрузку.
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::Duration;
+
+/// Сколько раз подряд повторять передачу после временной ошибки
+pub const MAX_RETRIES: u32 = 5;
+
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+/// Дольше этого по `Retry-After` не ждём: более долгое ожидание сокращается
+/// до 10 минут, после чего передача повторяется как обычно
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone)]
+pub struct TransferError {
+    pub message: String,
+    /// Ошибка временная — передачу стоит повторить
+    pub transient: bool,
+    /// Сколько ждать перед повтором по мнению сервера (`Retry-After`)
+    pub retry_after: Option<Duration>,
+    /// Не хватает места на диске
+    pub insufficient_space: bool,
+    /// Размер файла, если он оказался больше порога из настроек: загрузка
+    /// ждёт подтверждения
+    pub too_large: Option<i64>,
+}
+
+impl TransferError {
+    pub fn permanent(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            transient: false,
+            retry_after: None,
+            insufficient_space: false,
+            too_large: None,
+        }
+    }
+
+    pub fn transient(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            transient: true,
+            retry_after: None,
+            insufficient_space: false,
+            too_large: None,
+        }
+    }
+
+    /// Ошибка сети: всё, кроме неверного запроса и зацикленных редиректов,
+    /// считаем временным (обрыв, таймаут, сброс соединения)
+    pub fn network(e: reqwest::Error) -> Self {
+        if e.is_builder() || e.is_redirect() {
+            Self::permanent(e.to_string())
+        } else {
+            Self::transient(e.to_string())
+        }
+    }
+
+    /// Место на диске кончилось или опустилось ниже порога. Загрузку можно
+    /// продолжить, когда место освободят
+    pub fn insufficient_space(message: impl Into<String>) -> Self {
+        Self {
+            insufficient_space: true,
+            ..Self::permanent(message)
+        }
+    }
+
+    /// Файл размером `total_bytes` больше порога `limit` из настроек
+    pub fn too_large(total_bytes: i64, limit: u64) -> Self {
+        Self {
+            too_large: Some(total_bytes),
+            ..Self::permanent(format!("File is larger than {} bytes", limit))
+        }
+    }
+
+    /// Ошибка файловой системы (нет места, нет прав) повтором не лечится
+    pub fn io(e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::StorageFull {
+            return Self::insufficient_space(e.to_string());
+        }
+        Self::permanent(e.to_string())
+    }
+
+    /// Неуспешный HTTP-ответ
+    pub fn status(status: StatusCode, headers: &HeaderMap) -> Self {
+        let message = format!("HTTP error: {}", status);
+        let transient = status.is_server_error()
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS;
+        if !transient {
+            return Self::permanent(message);
+        }
+        Self {
+            retry_after: parse_retry_after(headers),
+            ..Self::transient(message)
+        }
+    }
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<TransferError> for String {
+    fn from(e: TransferError) -> Self {
+        e.message
+    }
+}
+
+/// `Retry-After: <секунды>` или `Retry-After: <HTTP-дата>`
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let seconds = (date.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(seconds as u64))
+}
+
+/// Задержка перед попыткой `attempt` (с 1): 1с, 2с, 4с… до минуты,
+/// со случайным разбросом в нижнюю половину интервала
+pub fn backoff(attempt: u32, retry_after: Option<Duration>) -> Duration {
+    let exponential = BASE_DELAY
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(MAX_DELAY);
+    let jittered = exponential.mul_f64(0.5 + rand::random::<f64>() * 0.5);
+    match retry_after {
+        Some(retry_after) => jittered.max(retry_after.min(MAX_RETRY_AFTER)),
+        None => jittered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn retry_after(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn classifies_status() {
+        // (статус, временная ли ошибка)
+        let cases: &[(u16, bool)] = &[
+            (500, true),
+            (502, true),
+            (503, true),
+            (504, true),
+            (408, true),
+            (429, true),
+            (400, false),
+            (401, false),
+            (403, false),
+            (404, false),
+            (410, false),
+            (416, false),
+        ];
+        for (code, transient) in cases {
+            let status = StatusCode::from_u16(*code).unwrap();
+            let error = TransferError::status(status, &retry_after("7"));
+            assert_eq!(error.transient, *transient, "status: {}", code);
+            assert!(!error.insufficient_space, "status: {}", code);
+            // Retry-After имеет смысл только для ошибок, которые повторяем
+            let expected = transient.then(|| Duration::from_secs(7));
+            assert_eq!(error.retry_after, expected, "status: {}", code);
+        }
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        let cases: &[(&str, Option<Duration>)] = &[
+            ("120", Some(Duration::from_secs(120))),
+            (" 5 ", Some(Duration::from_secs(5))),
+            ("0", Some(Duration::ZERO)),
+            // Дата в прошлом — можно повторять сразу
+            ("Wed, 21 Oct 2015 07:28:00 GMT", Some(Duration::ZERO)),
+            ("-1", None),
+            ("1.5", None),
+            ("soon", None),
+            ("", None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(parse_retry_after(&retry_after(value)), *expected, "value: {:?}", value);
+        }
+        assert_eq!(parse_retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn parses_retry_after_date_in_future() {
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(120))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let delay = parse_retry_after(&retry_after(&date)).unwrap();
+        assert!(
+            (Duration::from_secs(118)..=Duration::from_secs(120)).contains(&delay),
+            "delay: {:?}",
+            delay
+        );
+    }
+
+    #[test]
+    fn backoff_stays_within_bounds() {
+        // (попытка, верхняя граница без разброса)
+        let cases: &[(u32, u64)] = &[
+            (0, 1),
+            (1, 1),
+            (2, 2),
+            (3, 4),
+            (6, 32),
+            (7, 60),
+            (20, 60),
+            (u32::MAX, 60),
+        ];
+        for (attempt, max_secs) in cases {
+            let max = Duration::from_secs(*max_secs);
+            let delays: Vec<Duration> = (0..200).map(|_| backoff(*attempt, None)).collect();
+            for delay in &delays {
+                assert!(*delay >= max / 2 && *delay <= max, "attempt: {}, delay: {:?}", attempt, delay);
+            }
+            // Разброс есть — загрузки не переподключаются одновременно
+            assert!(delays.iter().any(|delay| *delay != delays[0]), "attempt: {}", attempt);
+        }
+    }
+
+    #[test]
+    fn backoff_respects_retry_after() {
+        // (попытка, Retry-After, ожидаемый минимум, ожидаемый максимум)
+        let cases: &[(u32, u64, u64, u64)] = &[
+            // Сервер просит подождать дольше нашей задержки
+            (1, 30, 30, 30),
+            // Наша задержка и так дольше
+            (7, 5, 30, 60),
+            // Слишком долгое ожидание ограничено десятью минутами
+            (1, 24 * 60 * 60, 600, 600),
+        ];
+        for (attempt, retry_after, min, max) in cases {
+            let delay = backoff(*attempt, Some(Duration::from_secs(*retry_after)));
+            assert!(
+                delay >= Duration::from_secs(*min) && delay <= Duration::from_secs(*max),
+                "attempt: {}, retry-after: {}, delay: {:?}",
+                attempt,
+                retry_after,
+                delay
+            );
+        }
+    }
+}
@@ -0,0 +1,686 @@
+//! Загрузка большого файла в несколько соединений
+//!
+//! Если сервер поддерживает `Range` (`Accept-Ranges: bytes`), файл делится на
+//! сегменты, которые качаются параллельно и пишутся каждый в своё место заранее
+//! выделенного файла. Освободившееся соединение забирает половину самого большого
+//! оставшегося куска (work stealing), поэтому медленный сегмент не задерживает
+//! окончание загрузки. Карта сегментов сохраняется рядом с файлом
+//! (`<файл>.segments`) — по ней загрузка продолжается после паузы или перезапуска.
+//!
+//! Если сервер всё же отдаёт файл целиком (200 вместо 206) или ресурс изменился,
+//! частичные данные удаляются и [`transfer`] возвращает `None` — вызывающий код
+//! качает файл в один поток.
+
+use futures_util::StreamExt;
+use reqwest::header::{IF_RANGE, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::watch;
+
+use super::provenance::ResponseRecord;
+use super::retry::{self, TransferError};
+use super::throttle::Throttle;
+use super::transfer::{
+    parse_content_range, Control, Progress, SegmentProgress, TransferEnd, TransferOutcome, Validator,
+    PROGRESS_INTERVAL,
+};
+use crate::storage;
+
+/// Файлы меньше этого размера качаются в одно соединение
+const MIN_SPLIT_SIZE: u64 = 8 * 1024 * 1024;
+/// Сегменты меньше этого размера не делятся дальше
+const MIN_SEGMENT_SIZE: u64 = 1024 * 1024;
+/// Повторные попытки одного сегмента после временной ошибки
+const SEGMENT_RETRIES: u32 = 3;
+/// Как часто карта сегментов сохраняется на диск во время загрузки
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Segment {
+    start: u64,
+    /// Конец сегмента (не включая)
+    end: u64,
+    received: u64,
+    /// Сегмент сейчас качает одно из соединений
+    #[serde(skip)]
+    active: bool,
+}
+
+impl Segment {
+    fn position(&self) -> u64 {
+        self.start + self.received
+    }
+
+    fn remaining(&self) -> u64 {
+        self.end.saturating_sub(self.position())
+    }
+}
+
+/// Содержимое `<файл>.segments`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SegmentMap {
+    #[serde(rename = "totalBytes")]
+    total_bytes: u64,
+    /// Версия ресурса, с которой начата загрузка
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(rename = "lastModified", default)]
+    last_modified: Option<String>,
+    segments: Vec<Segment>,
+}
+
+impl SegmentMap {
+    fn new(total_bytes: u64, connections: usize, validator: &Validator) -> Self {
+        let count = (connections as u64).clamp(1, (total_bytes / MIN_SEGMENT_SIZE).max(1));
+        let size = total_bytes / count;
+        let segments = (0..count)
+            .map(|i| Segment {
+                start: i * size,
+                end: if i + 1 == count { total_bytes } else { (i + 1) * size },
+                received: 0,
+                active: false,
+            })
+            .collect();
+
+        Self {
+            total_bytes,
+            etag: validator.etag.clone(),
+            last_modified: validator.last_modified.clone(),
+            segments,
+        }
+    }
+
+    fn validator(&self) -> Validator {
+        Validator {
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+        }
+    }
+}
+
+fn received(segments: &[Segment]) -> u64 {
+    segments.iter().map(|s| s.received).sum()
+}
+
+/// Занять сегмент для освободившегося соединения: сначала ещё не начатый
+/// или брошенный, иначе вторую половину самого большого из идущих
+fn claim(segments: &mut Vec<Segment>) -> Option<usize> {
+    if let Some(index) = segments.iter().position(|s| !s.active && s.remaining() > 0) {
+        segments[index].active = true;
+        return Some(index);
+    }
+
+    let victim = segments
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.active)
+        .max_by_key(|(_, s)| s.remaining())
+        .map(|(index, _)| index)?;
+    let remaining = segments[victim].remaining();
+    if remaining < 2 * MIN_SEGMENT_SIZE {
+        return None;
+    }
+
+    let middle = segments[victim].position() + remaining / 2;
+    let end = std::mem::replace(&mut segments[victim].end, middle);
+    segments.push(Segment {
+        start: middle,
+        end,
+        received: 0,
+        active: true,
+    });
+    Some(segments.len() - 1)
+}
+
+fn state_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".segments");
+    PathBuf::from(name)
+}
+
+/// Качать ли файл в несколько соединений. Без сильного валидатора (`If-Range`)
+/// сегменты могли бы собраться из разных версий файла.
+pub fn should_split(accept_ranges: bool, total_bytes: i64, connections: u32, validator: &Validator) -> bool {
+    accept_ranges
+        && connections > 1
+        && total_bytes >= MIN_SPLIT_SIZE as i64
+        && validator.if_range().is_some()
+}
+
+/// Сколько байт уже получено по сохранённой карте сегментов
+/// (`None`, если файл качается не по сегментам)
+pub async fn saved_progress(path: &Path) -> Option<i64> {
+    let map: SegmentMap = storage::read_json(&state_path(path)).await.ok()??;
+    Some(received(&map.segments) as i64)
+}
+
+/// Удалить частичный файл вместе с картой сегментов
+pub async fn discard(path: &Path) {
+    let _ = tokio::fs::remove_file(path).await;
+    let _ = storage::remove_json(&state_path(path)).await;
+}
+
+/// Перенести частичный файл вместе с картой сегментов
+pub async fn rename(from: &Path, to: &Path) -> std::io::Result<()> {
+    tokio::fs::rename(from, to).await?;
+    let map = state_path(from);
+    if tokio::fs::try_exists(&map).await.unwrap_or(false) {
+        tokio::fs::rename(&map, state_path(to)).await?;
+    }
+    Ok(())
+}
+
+enum SegmentError {
+    /// Сервер не отдал нужный диапазон — сегментная загрузка невозможна
+    Unsupported,
+    Failed(TransferError),
+}
+
+impl From<TransferError> for SegmentError {
+    fn from(e: TransferError) -> Self {
+        SegmentError::Failed(e)
+    }
+}
+
+struct Context<'a> {
+    client: &'a reqwest::Client,
+    url: &'a str,
+    path: &'a Path,
+    total_bytes: u64,
+    validator: Validator,
+    throttle: &'a Throttle,
+    segments: Mutex<Vec<Segment>>,
+    /// Первый принятый ответ — с него началась передача
+    response: Mutex<Option<ResponseRecord>>,
+}
+
+impl Context<'_> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Segment>> {
+        self.segments.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Скачать остаток сегмента `index` одним запросом
+async fn fetch_segment(ctx: &Context<'_>, index: usize) -> Result<(), SegmentError> {
+    let (position, end) = {
+        let segments = ctx.lock();
+        (segments[index].position(), segments[index].end)
+    };
+    if position >= end {
+        return Ok(());
+    }
+
+    let mut request = ctx
+        .client
+        .get(ctx.url)
+        .header(RANGE, format!("bytes={}-{}", position, end - 1));
+    if let Some(if_range) = ctx.validator.if_range() {
+        request = request.header(IF_RANGE, if_range);
+    }
+    let response = request.send().await.map_err(TransferError::network)?;
+
+    match response.status() {
+        StatusCode::PARTIAL_CONTENT => {}
+        // 200 — сервер игнорирует Range или ресурс изменился (If-Range не совпал)
+        status if status.is_success() => return Err(SegmentError::Unsupported),
+        status => return Err(TransferError::status(status, response.headers()).into()),
+    }
+    match parse_content_range(response.headers()) {
+        Some((Some(start), total)) if start == position && total.is_none_or(|t| t == ctx.total_bytes) => {}
+        _ => return Err(SegmentError::Unsupported),
+    }
+    ctx.response
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(|| ResponseRecord::of(&response));
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(ctx.path)
+        .await
+        .map_err(TransferError::io)?;
+    file.seek(SeekFrom::Start(position)).await.map_err(TransferError::io)?;
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(TransferError::network)?;
+
+        // Конец сегмента мог сдвинуться — его вторую половину забрало другое соединение
+        let take = ctx.lock()[index].remaining().min(chunk.len() as u64) as usize;
+        file.write_all(&chunk[..take]).await.map_err(TransferError::io)?;
+
+        let done = {
+            let mut segments = ctx.lock();
+            let segment = &mut segments[index];
+            segment.received = (segment.received + take as u64).min(segment.end - segment.start);
+            segment.remaining() == 0
+        };
+        ctx.throttle.acquire(take).await;
+        if done {
+            break;
+        }
+    }
+    file.flush().await.map_err(TransferError::io)?;
+
+    if ctx.lock()[index].remaining() > 0 {
+        return Err(TransferError::transient("Connection closed before the segment was complete").into());
+    }
+    Ok(())
+}
+
+/// Одно соединение: качает сегменты, пока есть что брать
+async fn worker(ctx: &Context<'_>) -> Result<(), SegmentError> {
+    loop {
+        let index = match claim(&mut ctx.lock()) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+
+        let mut attempt = 0;
+        let result = loop {
+            match fetch_segment(ctx, index).await {
+                // Временную ошибку повторяем в этом же соединении, остальные
+                // сегменты тем временем продолжают качаться
+                Err(SegmentError::Failed(e)) if e.transient && attempt < SEGMENT_RETRIES => {
+                    attempt += 1;
+                    tokio::time::sleep(retry::backoff(attempt, e.retry_after)).await;
+                }
+                result => break result,
+            }
+        };
+
+        ctx.lock()[index].active = false;
+        result?;
+    }
+}
+
+/// Скачать файл размером `total_bytes` в несколько соединений, продолжив по
+/// сохранённой карте сегментов, если она есть. `None` — сервер не поддерживает
+/// диапазоны, частичные данные удалены.
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    total_bytes: i64,
+    validator: &Validator,
+    connections: u32,
+    control: &mut watch::Receiver<Control>,
+    throttle: &Throttle,
+    mut on_progress: impl FnMut(Progress) -> Result<(), TransferError>,
+) -> Result<Option<TransferOutcome>, TransferError> {
+    let state = state_path(path);
+
+    let saved = storage::read_json::<SegmentMap>(&state).await.unwrap_or(None);
+    let map = match saved {
+        Some(map) if tokio::fs::metadata(path).await.is_ok() => map,
+        _ => {
+            if total_bytes <= 0 {
+                return Ok(None);
+            }
+            let map = SegmentMap::new(total_bytes as u64, connections as usize, validator);
+            let file = tokio::fs::File::create(path)
+                .await
+                .map_err(TransferError::io)?;
+            file.set_len(map.total_bytes)
+                .await
+                .map_err(TransferError::io)?;
+            storage::write_json(&state, &map).await.map_err(TransferError::permanent)?;
+            map
+        }
+    };
+
+    let ctx = Context {
+        client,
+        url,
+        path,
+        total_bytes: map.total_bytes,
+        validator: map.validator(),
+        throttle,
+        segments: Mutex::new(map.segments),
+        response: Mutex::new(None),
+    };
+    let snapshot = |ctx: &Context<'_>| SegmentMap {
+        total_bytes: ctx.total_bytes,
+        etag: ctx.validator.etag.clone(),
+        last_modified: ctx.validator.last_modified.clone(),
+        segments: ctx.lock().clone(),
+    };
+    let outcome = |end, received_bytes: u64| TransferOutcome {
+        end,
+        received_bytes: received_bytes as i64,
+        total_bytes: ctx.total_bytes as i64,
+        validator: ctx.validator.clone(),
+        // Куски приходят не по порядку — сумму считают по готовому файлу
+        digest: None,
+        response: ctx.response.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+    };
+
+    if *control.borrow() == Control::Pause {
+        return Ok(Some(outcome(TransferEnd::Paused, received(&ctx.lock()))));
+    }
+
+    let result = {
+        let workers = futures_util::future::try_join_all(
+            (0..connections.max(1)).map(|_| worker(&ctx)),
+        );
+        tokio::pin!(workers);
+
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+        let mut last_save = Instant::now();
+        let mut control_open = true;
+
+        loop {
+            let command = *control.borrow();
+            if command != Control::Run {
+                break Err(command);
+            }
+
+            tokio::select! {
+                result = &mut workers => break Ok(result),
+                changed = control.changed(), if control_open => {
+                    control_open = changed.is_ok();
+                }
+                _ = ticker.tick() => {
+                    let (received_bytes, segments) = {
+                        let segments = ctx.lock();
+                        let progress = segments
+                            .iter()
+                            .map(|s| SegmentProgress {
+                                start: s.start as i64,
+                                end: s.end as i64,
+                                received: s.received as i64,
+                            })
+                            .collect::<Vec<_>>();
+                        (received(&segments), progress)
+                    };
+
+                    let progress = on_progress(Progress {
+                        received_bytes: received_bytes as i64,
+                        total_bytes: ctx.total_bytes as i64,
+                        segments,
+                    });
+                    if let Err(e) = progress {
+                        break Ok(Err(SegmentError::Failed(e)));
+                    }
+
+                    let now = Instant::now();
+                    if now.duration_since(last_save) >= SAVE_INTERVAL {
+                        let _ = storage::write_json(&state, &snapshot(&ctx)).await;
+                        last_save = now;
+                    }
+                }
+            }
+        }
+    };
+
+    let received_bytes = received(&ctx.lock());
+    match result {
+        Ok(Ok(_)) => {
+            let _ = storage::remove_json(&state).await;
+            Ok(Some(outcome(TransferEnd::Completed, ctx.total_bytes)))
+        }
+        Ok(Err(SegmentError::Unsupported)) => {
+            discard(path).await;
+            Ok(None)
+        }
+        Ok(Err(SegmentError::Failed(e))) => {
+            storage::write_json(&state, &snapshot(&ctx)).await.map_err(TransferError::permanent)?;
+            Err(e)
+        }
+        Err(Control::Cancel) => {
+            discard(path).await;
+            Ok(Some(outcome(TransferEnd::Cancelled, received_bytes)))
+        }
+        Err(_) => {
+            storage::write_json(&state, &snapshot(&ctx)).await.map_err(TransferError::permanent)?;
+            Ok(Some(outcome(TransferEnd::Paused, received_bytes)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve_file, FileConfig, TempDir, TestServer};
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+
+    const FILE: &str = "/file.bin";
+    const MIB: u64 = 1024 * 1024;
+
+    fn segment(start: u64, end: u64, received: u64, active: bool) -> Segment {
+        Segment {
+            start,
+            end,
+            received,
+            active,
+        }
+    }
+
+    fn strong_validator() -> Validator {
+        Validator {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        }
+    }
+
+    fn test_body() -> Vec<u8> {
+        (0..MIN_SPLIT_SIZE).map(|i| (i % 251) as u8).collect()
+    }
+
+    async fn file_server(body: &[u8], etag: &str, honor_ranges: bool) -> TestServer {
+        serve_file(FileConfig {
+            body: body.to_vec(),
+            etag: Some(etag.to_string()),
+            honor_ranges,
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn run(server: &TestServer, path: &Path, total_bytes: i64) -> Option<TransferOutcome> {
+        let (_tx, mut rx) = watch::channel(Control::Run);
+        transfer(&reqwest::Client::new(), &server.url(FILE), path, total_bytes, &strong_validator(), 4, &mut rx, &Throttle::unlimited(), |_| Ok(()))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn splits_only_large_files_with_validator() {
+        let weak = Validator {
+            etag: Some("W/\"v1\"".to_string()),
+            last_modified: None,
+        };
+        let cases: &[(bool, i64, u32, &Validator, bool)] = &[
+            (true, MIN_SPLIT_SIZE as i64, 4, &strong_validator(), true),
+            (true, 1 << 40, 2, &strong_validator(), true),
+            (false, MIN_SPLIT_SIZE as i64, 4, &strong_validator(), false),
+            (true, MIN_SPLIT_SIZE as i64 - 1, 4, &strong_validator(), false),
+            (true, -1, 4, &strong_validator(), false),
+            (true, MIN_SPLIT_SIZE as i64, 1, &strong_validator(), false),
+            (true, MIN_SPLIT_SIZE as i64, 4, &weak, false),
+            (true, MIN_SPLIT_SIZE as i64, 4, &Validator::default(), false),
+        ];
+        for (accept_ranges, total_bytes, connections, validator, expected) in cases {
+            assert_eq!(
+                should_split(*accept_ranges, *total_bytes, *connections, validator),
+                *expected,
+                "ranges: {}, size: {}, connections: {}, validator: {:?}",
+                accept_ranges,
+                total_bytes,
+                connections,
+                validator
+            );
+        }
+    }
+
+    #[test]
+    fn claims_free_segments_first() {
+        // Готовый сегмент пропускается, брошенный (неактивный) забирается раньше деления
+        let mut segments = vec![
+            segment(0, 4 * MIB, 4 * MIB, false),
+            segment(4 * MIB, 8 * MIB, MIB, true),
+            segment(8 * MIB, 12 * MIB, MIB, false),
+        ];
+        assert_eq!(claim(&mut segments), Some(2));
+        assert!(segments[2].active);
+        assert_eq!(segments.len(), 3);
+    }
+
+    #[test]
+    fn steals_half_of_largest_active_segment() {
+        let mut segments = vec![
+            segment(0, 4 * MIB, 3 * MIB, true),
+            segment(4 * MIB, 8 * MIB, MIB, true),
+        ];
+        assert_eq!(claim(&mut segments), Some(2));
+
+        // Второй сегмент: осталось 3 МиБ с позиции 5 МиБ — делится на 6,5 МиБ
+        let middle = 5 * MIB + 3 * MIB / 2;
+        assert_eq!(segments[1].end, middle);
+        assert_eq!((segments[2].start, segments[2].end, segments[2].received), (middle, 8 * MIB, 0));
+        assert!(segments[2].active);
+        assert_eq!(received(&segments), 4 * MIB);
+    }
+
+    #[test]
+    fn small_remainders_are_not_split() {
+        let mut segments = vec![
+            segment(0, 4 * MIB, 4 * MIB - (2 * MIB - 1), true),
+            segment(4 * MIB, 8 * MIB, 4 * MIB, false),
+        ];
+        assert_eq!(claim(&mut segments), None);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].end, 4 * MIB);
+    }
+
+    #[tokio::test]
+    async fn downloads_in_several_connections() {
+        let body = test_body();
+        let server = file_server(&body, "\"v1\"", true).await;
+        let dir = TempDir::new("segmented");
+        let path = dir.join("file.bin");
+
+        let outcome = run(&server, &path, body.len() as i64).await.unwrap();
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(outcome.received_bytes, body.len() as i64);
+        assert!(outcome.response.is_some());
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        // Карта сегментов больше не нужна
+        assert!(!state_path(&path).exists());
+
+        let requests = server.requests();
+        assert!(requests.len() >= 4, "requests: {}", requests.len());
+        for request in &requests {
+            assert!(request.header("range").is_some());
+            assert_eq!(request.header("if-range"), Some("\"v1\""));
+        }
+        assert!(requests.iter().any(|r| r.header("range") == Some("bytes=0-2097151")));
+    }
+
+    #[tokio::test]
+    async fn resumes_from_saved_map_after_pause() {
+        let body = test_body();
+        let stalled = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: true,
+            stall_after: Some(64 * 1024),
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("segmented");
+        let path = dir.join("file.bin");
+        let (tx, mut rx) = watch::channel(Control::Run);
+        let progress = Arc::new(AtomicI64::new(0));
+
+        let task = {
+            let url = stalled.url(FILE);
+            let path = path.clone();
+            let progress = progress.clone();
+            let total_bytes = body.len() as i64;
+            tokio::spawn(async move {
+                let on_progress = |p: Progress| {
+                    progress.store(p.received_bytes, Ordering::SeqCst);
+                    Ok(())
+                };
+                transfer(&reqwest::Client::new(), &url, &path, total_bytes, &strong_validator(), 4, &mut rx, &Throttle::unlimited(), on_progress).await
+            })
+        };
+        // Каждое из четырёх соединений получило начало своего сегмента
+        for _ in 0..500 {
+            if progress.load(Ordering::SeqCst) >= 4 * 64 * 1024 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tx.send(Control::Pause).unwrap();
+
+        let paused = task.await.unwrap().unwrap().unwrap();
+        assert_eq!(paused.end, TransferEnd::Paused);
+        assert_eq!(paused.received_bytes, 4 * 64 * 1024);
+        assert_eq!(saved_progress(&path).await, Some(paused.received_bytes));
+
+        // Докачка начинается с сохранённых позиций, а не с начала сегментов
+        let server = file_server(&body, "\"v1\"", true).await;
+        let outcome = run(&server, &path, body.len() as i64).await.unwrap();
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert!(!state_path(&path).exists());
+
+        let requests = server.requests();
+        assert!(requests.iter().any(|r| r.header("range") == Some("bytes=65536-2097151")));
+        assert!(!requests.iter().any(|r| r.header("range").is_some_and(|range| range.starts_with("bytes=0-"))));
+    }
+
+    #[tokio::test]
+    async fn retries_failed_segment() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: true,
+            fail_first: Some("503 Service Unavailable"),
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("segmented");
+        let path = dir.join("file.bin");
+
+        let outcome = run(&server, &path, body.len() as i64).await.unwrap();
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert!(server.requests().len() >= 5);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_single_stream() {
+        let body = test_body();
+        // (ETag на сервере, отдаёт ли диапазоны, размер, ожидаемый загрузкой)
+        let cases: &[(&str, bool, i64)] = &[
+            // Сервер игнорирует Range и отвечает 200
+            ("\"v1\"", false, body.len() as i64),
+            // Ресурс изменился — If-Range не совпал
+            ("\"v2\"", true, body.len() as i64),
+            // Content-Range говорит о другом размере
+            ("\"v1\"", true, body.len() as i64 + 1),
+        ];
+        for (etag, honor_ranges, total_bytes) in cases {
+            let server = file_server(&body, etag, *honor_ranges).await;
+            let dir = TempDir::new("segmented");
+            let path = dir.join("file.bin");
+
+            assert!(run(&server, &path, *total_bytes).await.is_none(), "etag: {}, ranges: {}", etag, honor_ranges);
+            // Частичные данные и карта удалены
+            assert!(!path.exists() && !state_path(&path).exists(), "etag: {}, ranges: {}", etag, honor_ranges);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Свободное место на диске
+//!
+//! Перед передачей проверяем, что остаток файла поместится и на диске
+//! останется не меньше [`FREE_SPACE_FLOOR`]. Во время передачи место
+//! проверяется раз в [`CHECK_INTERVAL`]: если его заняли другие программы
+//! и оно опустилось ниже порога, загрузка останавливается.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::retry::TransferError;
+
+/// Сколько места оставлять свободным
+pub const FREE_SPACE_FLOOR: u64 = 256 * 1024 * 1024;
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 1536 → `1.5 KB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Хватит ли `available` байт на `needed`, не опускаясь ниже порога
+fn check_available(needed: u64, available: u64) -> Result<(), TransferError> {
+    if available >= needed.saturating_add(FREE_SPACE_FLOOR) {
+        return Ok(());
+    }
+    let message = if needed == 0 {
+        format!("Not enough disk space: {} left", format_size(available))
+    } else {
+        format!(
+            "Not enough disk space: {} needed, {} available",
+            format_size(needed),
+            format_size(available)
+        )
+    };
+    Err(TransferError::insufficient_space(message))
+}
+
+/// Проверить место в папке `dir` под ещё `needed` байт. Если узнать
+/// свободное место не удалось, загрузку не останавливаем.
+pub fn check(dir: &Path, needed: u64) -> Result<(), TransferError> {
+    match fs2::available_space(dir) {
+        Ok(available) => check_available(needed, available),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Проверка места во время передачи — не чаще раза в [`CHECK_INTERVAL`]
+pub struct SpaceGuard {
+    dir: PathBuf,
+    last_check: Instant,
+}
+
+impl SpaceGuard {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            last_check: Instant::now(),
+        }
+    }
+
+    pub fn poll(&mut self) -> Result<(), TransferError> {
+        let now = Instant::now();
+        if now.duration_since(self.last_check) < CHECK_INTERVAL {
+            return Ok(());
+        }
+        self.last_check = now;
+        check(&self.dir, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_free_space_floor() {
+        let gb = 1024 * 1024 * 1024;
+        assert!(check_available(gb, 2 * gb).is_ok());
+        assert!(check_available(0, FREE_SPACE_FLOOR).is_ok());
+
+        let error = check_available(2 * gb, 2 * gb).unwrap_err();
+        assert!(error.insufficient_space && !error.transient);
+        assert_eq!(error.message, "Not enough disk space: 2.0 GB needed, 2.0 GB available");
+
+        let error = check_available(0, FREE_SPACE_FLOOR - 1).unwrap_err();
+        assert_eq!(error.message, "Not enough disk space: 256.0 MB left");
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
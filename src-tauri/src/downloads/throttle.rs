@@ -0,0 +1,208 @@
+//! Ограничение скорости загрузок (token bucket)
+//!
+//! Общий лимит делят все загрузки; лимит отдельной загрузки, если задан,
+//! заменяет для неё общий. Лимиты можно менять на лету — ожидающие передачи
+//! сразу пересчитывают паузу, а заданный свой лимит сразу заменяет общий.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+struct Bucket {
+    /// Байт в секунду; `None` — лимит не задан, `Some(0)` — без ограничений
+    limit: Option<u64>,
+    /// Доступные байты; отрицательное значение — долг, который нужно «отстоять»
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, rate: u64) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        // Запас не больше секунды трафика, чтобы после простоя не было всплеска
+        self.tokens = (self.tokens + elapsed * rate as f64).min(rate as f64);
+        self.updated = now;
+    }
+}
+
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+    changed: Notify,
+}
+
+impl RateLimiter {
+    pub fn new(limit: Option<u64>) -> Self {
+        Self {
+            bucket: Mutex::new(Bucket {
+                limit,
+                tokens: 0.0,
+                updated: Instant::now(),
+            }),
+            changed: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        self.lock().limit
+    }
+
+    /// Сменить лимит; идущие передачи подхватят его сразу
+    pub fn set_limit(&self, limit: Option<u64>) {
+        {
+            let mut bucket = self.lock();
+            bucket.limit = limit;
+            bucket.tokens = 0.0;
+            bucket.updated = Instant::now();
+        }
+        self.changed.notify_waiters();
+    }
+
+    /// Списать `bytes` и подождать, если лимит превышен
+    pub async fn acquire(&self, bytes: usize) {
+        let mut charged = false;
+        loop {
+            let changed = self.changed.notified();
+            let wait = {
+                let mut bucket = self.lock();
+                let rate = match bucket.limit {
+                    Some(rate) if rate > 0 => rate,
+                    _ => return,
+                };
+                bucket.refill(rate);
+                if !charged {
+                    bucket.tokens -= bytes as f64;
+                    charged = true;
+                }
+                if bucket.tokens >= 0.0 {
+                    return;
+                }
+                Duration::from_secs_f64(-bucket.tokens / rate as f64)
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => return,
+                // Лимит поменялся — долг обнулён, проверяем заново
+                _ = changed => {}
+            }
+        }
+    }
+}
+
+/// Лимиты, действующие для одной передачи
+#[derive(Clone)]
+pub struct Throttle {
+    global: Arc<RateLimiter>,
+    own: Arc<RateLimiter>,
+}
+
+impl Throttle {
+    pub fn new(global: Arc<RateLimiter>, own: Arc<RateLimiter>) -> Self {
+        Self { global, own }
+    }
+
+    /// Без ограничений
+    pub fn unlimited() -> Self {
+        Self::new(Arc::new(RateLimiter::new(None)), Arc::new(RateLimiter::new(None)))
+    }
+
+    /// Свой лимит, заданный, пока передача ждёт общий, действует сразу:
+    /// ожидание переключается на него
+    pub async fn acquire(&self, bytes: usize) {
+        loop {
+            let own_changed = self.own.changed.notified();
+            if self.own.limit().is_some() {
+                return self.own.acquire(bytes).await;
+            }
+            tokio::select! {
+                _ = self.global.acquire(bytes) => return,
+                _ = own_changed => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limit: Option<u64>) -> Arc<RateLimiter> {
+        Arc::new(RateLimiter::new(limit))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keeps_steady_rate() {
+        let limiter = limiter(Some(1000));
+        let started = Instant::now();
+        for _ in 0..10 {
+            limiter.acquire(500).await;
+        }
+        // 5000 байт при 1000 байт/с
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(4900) && elapsed <= Duration::from_millis(5100), "{:?}", elapsed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_limit_means_unlimited() {
+        for limit in [None, Some(0)] {
+            let started = Instant::now();
+            limiter(limit).acquire(usize::MAX / 2).await;
+            assert_eq!(started.elapsed(), Duration::ZERO, "limit: {:?}", limit);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn new_limit_wakes_waiting_transfer() {
+        let limiter = limiter(Some(100));
+        let started = Instant::now();
+        // При 100 байт/с ждать пришлось бы 10 секунд
+        let task = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(1000).await }
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        limiter.set_limit(Some(0));
+
+        task.await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(1100), "{:?}", started.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn own_limit_takes_precedence() {
+        // (общий лимит, свой лимит, сколько займут 10 000 байт)
+        let cases: &[(Option<u64>, Option<u64>, u64)] = &[
+            (Some(100), Some(10_000), 1),
+            (Some(10_000), Some(1000), 10),
+            (Some(1000), None, 10),
+            (Some(100), Some(0), 0),
+        ];
+        for (global, own, seconds) in cases {
+            let throttle = Throttle::new(limiter(*global), limiter(*own));
+            let started = Instant::now();
+            throttle.acquire(10_000).await;
+            let elapsed = started.elapsed().as_secs_f64();
+            assert!((elapsed - *seconds as f64).abs() < 0.1, "global: {:?}, own: {:?}, elapsed: {}", global, own, elapsed);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn own_limit_set_while_waiting_on_global() {
+        let own = limiter(None);
+        let throttle = Throttle::new(limiter(Some(100)), own.clone());
+        let started = Instant::now();
+        let task = tokio::spawn(async move { throttle.acquire(1000).await });
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        own.set_limit(Some(10_000));
+
+        task.await.unwrap();
+        // 1000 байт по своему лимиту — десятая доля секунды, а не 10 секунд по общему
+        let elapsed = started.elapsed();
+        assert!(elapsed <= Duration::from_millis(1200), "{:?}", elapsed);
+    }
+}
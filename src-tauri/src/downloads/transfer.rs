@@ -0,0 +1,666 @@
+//! HTTP-передача одного файла: пауза, отмена и докачка через `Range`
+//!
+//! Модуль не зависит от Tauri: прогресс отдаётся через колбэк, а команды
+//! (пауза/отмена) приходят через watch-канал. Частично скачанный файл остаётся
+//! на диске; при следующем запуске передача продолжается с его конца, если
+//! сервер подтверждает, что ресурс не изменился (`If-Range`), иначе файл
+//! скачивается заново.
+
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+
+use super::checksum::{self, HashAlgorithm, Hasher};
+use super::provenance::ResponseRecord;
+use super::retry::TransferError;
+use super::throttle::Throttle;
+
+pub(super) const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Команда для идущей передачи
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Чем закончилась передача
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferEnd {
+    Completed,
+    /// Частичный файл сохранён, передачу можно продолжить
+    Paused,
+    /// Частичный файл удалён
+    Cancelled,
+}
+
+/// Признаки версии ресурса для `If-Range`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validator {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validator {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string())
+        };
+        Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        }
+    }
+
+    /// Значение для `If-Range`: сильный ETag, иначе Last-Modified
+    /// (слабые ETag в `If-Range` использовать нельзя, RFC 9110 §13.1.5)
+    pub(super) fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+/// Сколько скачано; скорость по этим отсчётам считает [`super::progress::SpeedMeter`]
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub received_bytes: i64,
+    pub total_bytes: i64,
+    /// Сегменты при загрузке в несколько соединений (пусто для одного потока)
+    pub segments: Vec<SegmentProgress>,
+}
+
+/// Прогресс одного сегмента `[start, end)`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SegmentProgress {
+    pub start: i64,
+    pub end: i64,
+    pub received: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferOutcome {
+    pub end: TransferEnd,
+    pub received_bytes: i64,
+    /// -1, если размер неизвестен
+    pub total_bytes: i64,
+    pub validator: Validator,
+    /// Контрольная сумма готового файла (`sha256:<hex>`), если её просили посчитать
+    pub digest: Option<String>,
+    /// Ответ, с которого началась передача (нет, если данные не запрашивались)
+    pub response: Option<ResponseRecord>,
+}
+
+/// Разобрать `Content-Range: bytes <start>-<end>/<total>` (или `bytes */<total>`)
+pub(super) fn parse_content_range(headers: &HeaderMap) -> Option<(Option<u64>, Option<u64>)> {
+    let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let rest = value.trim().strip_prefix("bytes")?.trim_start();
+    let (range, total) = rest.split_once('/')?;
+    let start = range
+        .split_once('-')
+        .and_then(|(start, _)| start.trim().parse().ok());
+    let total = total.trim().parse().ok();
+    Some((start, total))
+}
+
+/// Что делать с ответом на запрос с `Range`
+enum Resume {
+    /// Сервер отдаёт продолжение — дописываем в конец файла
+    Append { total_bytes: i64 },
+    /// Файл уже скачан полностью
+    AlreadyComplete,
+    /// Продолжить нельзя — начинаем с нуля
+    Restart,
+    /// Сервер ответил ошибкой — частичный файл не трогаем, решает повтор
+    Failed,
+}
+
+fn check_resume(
+    status: StatusCode,
+    headers: &HeaderMap,
+    offset: u64,
+    known_total: i64,
+    validator: &Validator,
+) -> Resume {
+    match status {
+        StatusCode::PARTIAL_CONTENT => match parse_content_range(headers) {
+            Some((Some(start), total)) if start == offset => {
+                // Сервер мог отдать кусок другой версии ресурса
+                let etag = Validator::from_headers(headers).etag;
+                if validator.etag.is_some() && etag.is_some() && etag != validator.etag {
+                    return Resume::Restart;
+                }
+                // Без валидатора сверяем хотя бы размер ресурса
+                if known_total > 0 && total.is_some_and(|t| t as i64 != known_total) {
+                    return Resume::Restart;
+                }
+                Resume::Append {
+                    total_bytes: total.map(|t| t as i64).unwrap_or(-1),
+                }
+            }
+            _ => Resume::Restart,
+        },
+        StatusCode::RANGE_NOT_SATISFIABLE => match parse_content_range(headers) {
+            Some((_, Some(total))) if total == offset => Resume::AlreadyComplete,
+            _ => Resume::Restart,
+        },
+        // Сервер не поддерживает Range или ресурс изменился (If-Range не совпал)
+        StatusCode::OK => Resume::Restart,
+        _ => Resume::Failed,
+    }
+}
+
+/// Скачать `url` в `path`, продолжив с конца уже существующего файла.
+/// `known_total` — размер из предыдущей попытки (-1, если неизвестен).
+/// С `algorithm` контрольная сумма считается по ходу передачи.
+/// Ошибка из `on_progress` останавливает передачу.
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer(
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    known_total: i64,
+    validator: &Validator,
+    control: &mut watch::Receiver<Control>,
+    throttle: &Throttle,
+    algorithm: Option<HashAlgorithm>,
+    mut on_progress: impl FnMut(Progress) -> Result<(), TransferError>,
+) -> Result<TransferOutcome, TransferError> {
+    let mut offset = match tokio::fs::metadata(path).await {
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    };
+
+    if *control.borrow() == Control::Pause {
+        return Ok(TransferOutcome {
+            end: TransferEnd::Paused,
+            received_bytes: offset as i64,
+            total_bytes: known_total,
+            validator: validator.clone(),
+            digest: None,
+            response: None,
+        });
+    }
+
+    let (response, mut file, mut received_bytes, total_bytes) = loop {
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+            if let Some(if_range) = validator.if_range() {
+                request = request.header(IF_RANGE, if_range);
+            }
+        }
+
+        let response = request.send().await.map_err(TransferError::network)?;
+        let status = response.status();
+
+        if offset > 0 {
+            match check_resume(status, response.headers(), offset, known_total, validator) {
+                Resume::Append { total_bytes } => {
+                    let file = tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(path)
+                        .await
+                        .map_err(TransferError::io)?;
+                    break (response, file, offset as i64, total_bytes);
+                }
+                Resume::AlreadyComplete => {
+                    let digest = match algorithm {
+                        Some(algorithm) => Some(
+                            checksum::hash_file(path, algorithm)
+                                .await
+                                .map_err(TransferError::permanent)?
+                                .to_string(),
+                        ),
+                        None => None,
+                    };
+                    return Ok(TransferOutcome {
+                        end: TransferEnd::Completed,
+                        received_bytes: offset as i64,
+                        total_bytes: offset as i64,
+                        validator: validator.clone(),
+                        digest,
+                        response: None,
+                    });
+                }
+                Resume::Restart if status == StatusCode::OK => {
+                    // Сервер уже отдаёт файл целиком — используем этот ответ
+                }
+                Resume::Restart => {
+                    offset = 0;
+                    continue;
+                }
+                Resume::Failed => {
+                    return Err(TransferError::status(status, response.headers()));
+                }
+            }
+        }
+
+        if !status.is_success() {
+            return Err(TransferError::status(status, response.headers()));
+        }
+
+        let total_bytes = response.content_length().map(|l| l as i64).unwrap_or(-1);
+        let file = tokio::fs::File::create(path)
+            .await
+            .map_err(TransferError::io)?;
+        break (response, file, 0, total_bytes);
+    };
+
+    // Размер мог стать известен только из этого ответа — сообщаем его до
+    // первых данных, чтобы обработчик успел остановить передачу
+    on_progress(Progress {
+        received_bytes,
+        total_bytes,
+        segments: Vec::new(),
+    })?;
+
+    let validator = {
+        let fresh = Validator::from_headers(response.headers());
+        if fresh.if_range().is_some() {
+            fresh
+        } else {
+            validator.clone()
+        }
+    };
+    let record = ResponseRecord::of(&response);
+    let outcome = |end, received_bytes| TransferOutcome {
+        end,
+        received_bytes,
+        total_bytes,
+        validator: validator.clone(),
+        digest: None,
+        response: Some(record.clone()),
+    };
+
+    // При докачке сумма начинается с уже записанной части файла
+    let mut hasher = match algorithm {
+        Some(algorithm) if received_bytes > 0 => Some(
+            Hasher::from_file(algorithm, path)
+                .await
+                .map_err(TransferError::permanent)?,
+        ),
+        Some(algorithm) => Some(Hasher::new(algorithm)),
+        None => None,
+    };
+
+    let mut stream = response.bytes_stream();
+    let mut last_update = Instant::now();
+    let mut control_open = true;
+
+    loop {
+        let command = *control.borrow();
+        match command {
+            Control::Run => {}
+            Control::Pause => {
+                file.flush().await.map_err(TransferError::io)?;
+                return Ok(outcome(TransferEnd::Paused, received_bytes));
+            }
+            Control::Cancel => {
+                drop(file);
+                let _ = tokio::fs::remove_file(path).await;
+                return Ok(outcome(TransferEnd::Cancelled, received_bytes));
+            }
+        }
+
+        // Ждём данные или команду — пауза срабатывает, даже если сервер замолчал
+        let chunk = tokio::select! {
+            chunk = stream.next() => chunk,
+            changed = control.changed(), if control_open => {
+                control_open = changed.is_ok();
+                continue;
+            }
+        };
+
+        let chunk = match chunk {
+            Some(chunk) => chunk.map_err(TransferError::network)?,
+            None => break,
+        };
+        file.write_all(&chunk).await.map_err(TransferError::io)?;
+        if let Some(hasher) = hasher.as_mut() {
+            hasher.update(&chunk);
+        }
+        received_bytes += chunk.len() as i64;
+
+        // Ограничение скорости; пауза и отмена срабатывают и во время ожидания
+        tokio::select! {
+            _ = throttle.acquire(chunk.len()) => {}
+            changed = control.changed(), if control_open => {
+                control_open = changed.is_ok();
+            }
+        }
+
+        let now = Instant::now();
+        if now.duration_since(last_update) >= PROGRESS_INTERVAL {
+            on_progress(Progress {
+                received_bytes,
+                total_bytes,
+                segments: Vec::new(),
+            })?;
+            last_update = now;
+        }
+    }
+
+    file.flush().await.map_err(TransferError::io)?;
+
+    // Соединение оборвалось раньше, чем пришёл весь файл
+    if total_bytes > 0 && received_bytes < total_bytes {
+        return Err(TransferError::transient(format!(
+            "Connection closed after {} of {} bytes",
+            received_bytes, total_bytes
+        )));
+    }
+
+    Ok(TransferOutcome {
+        total_bytes: if total_bytes > 0 { total_bytes } else { received_bytes },
+        digest: hasher.map(|hasher| hasher.finalize().to_string()),
+        ..outcome(TransferEnd::Completed, received_bytes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve_file, FileConfig, TempDir, TestServer};
+
+    const FILE: &str = "/file.bin";
+
+    fn test_body() -> Vec<u8> {
+        (0..64 * 1024).map(|i| (i % 251) as u8).collect()
+    }
+
+    async fn run(server: &TestServer, path: &Path, known_total: i64, validator: &Validator) -> TransferOutcome {
+        let (_tx, mut rx) = watch::channel(Control::Run);
+        transfer(&reqwest::Client::new(), &server.url(FILE), path, known_total, validator, &mut rx, &Throttle::unlimited(), Some(HashAlgorithm::Sha256), |_| Ok(()))
+            .await
+            .unwrap()
+    }
+
+    fn sha256(data: &[u8]) -> String {
+        let mut hasher = Hasher::new(HashAlgorithm::Sha256);
+        hasher.update(data);
+        hasher.finalize().to_string()
+    }
+
+    /// Дождаться, пока файл вырастет до `len` байт
+    async fn wait_for_len(path: &Path, len: u64) {
+        for _ in 0..500 {
+            if std::fs::metadata(path).map(|m| m.len() >= len).unwrap_or(false) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("file did not reach {} bytes", len);
+    }
+
+    #[tokio::test]
+    async fn downloads_whole_file() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: true,
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+
+        let outcome = run(&server, &path, -1, &Validator::default()).await;
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(outcome.total_bytes, body.len() as i64);
+        assert_eq!(outcome.validator.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(outcome.digest, Some(sha256(&body)));
+        let response = outcome.response.unwrap();
+        assert_eq!(response.url, server.url(FILE));
+        assert_eq!(response.headers["etag"], "\"v1\"");
+        assert_eq!(server.requests()[0].header("range"), None);
+    }
+
+    #[tokio::test]
+    async fn pause_keeps_partial_file() {
+        let server = serve_file(FileConfig {
+            body: test_body(),
+            honor_ranges: true,
+            stall_after: Some(1000),
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        let (tx, mut rx) = watch::channel(Control::Run);
+
+        let task = {
+            let url = server.url(FILE);
+            let path = path.clone();
+            tokio::spawn(async move {
+                transfer(&reqwest::Client::new(), &url, &path, -1, &Validator::default(), &mut rx, &Throttle::unlimited(), None, |_| Ok(())).await
+            })
+        };
+        wait_for_len(&path, 1000).await;
+        tx.send(Control::Pause).unwrap();
+
+        let outcome = task.await.unwrap().unwrap();
+        assert_eq!(outcome.end, TransferEnd::Paused);
+        assert_eq!(outcome.received_bytes, 1000);
+        assert_eq!(std::fs::read(&path).unwrap(), test_body()[..1000]);
+    }
+
+    #[tokio::test]
+    async fn cancel_removes_partial_file() {
+        let server = serve_file(FileConfig {
+            body: test_body(),
+            stall_after: Some(1000),
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        let (tx, mut rx) = watch::channel(Control::Run);
+
+        let task = {
+            let url = server.url(FILE);
+            let path = path.clone();
+            tokio::spawn(async move {
+                transfer(&reqwest::Client::new(), &url, &path, -1, &Validator::default(), &mut rx, &Throttle::unlimited(), None, |_| Ok(())).await
+            })
+        };
+        wait_for_len(&path, 1000).await;
+        tx.send(Control::Cancel).unwrap();
+
+        assert_eq!(task.await.unwrap().unwrap().end, TransferEnd::Cancelled);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn resumes_with_range_and_if_range() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: true,
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        std::fs::write(&path, &body[..1000]).unwrap();
+        let validator = Validator {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let outcome = run(&server, &path, body.len() as i64, &validator).await;
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(outcome.received_bytes, body.len() as i64);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        // Сумма учитывает и часть файла, скачанную до докачки
+        assert_eq!(outcome.digest, Some(sha256(&body)));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("range"), Some("bytes=1000-"));
+        assert_eq!(requests[0].header("if-range"), Some("\"v1\""));
+    }
+
+    #[tokio::test]
+    async fn restarts_when_server_ignores_range() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: false,
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        std::fs::write(&path, &body[..1000]).unwrap();
+        let validator = Validator {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let outcome = run(&server, &path, body.len() as i64, &validator).await;
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn restarts_when_resource_changed() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v2\"".to_string()),
+            honor_ranges: true,
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        // Начало старой версии файла
+        std::fs::write(&path, vec![0xAA; 1000]).unwrap();
+        let validator = Validator {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let outcome = run(&server, &path, body.len() as i64, &validator).await;
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(outcome.validator.etag.as_deref(), Some("\"v2\""));
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(outcome.digest, Some(sha256(&body)));
+    }
+
+    #[tokio::test]
+    async fn server_error_keeps_partial_file_for_retry() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: true,
+            fail_first: Some("503 Service Unavailable"),
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        std::fs::write(&path, &body[..1000]).unwrap();
+        let validator = Validator {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let (_tx, mut rx) = watch::channel(Control::Run);
+        let error = transfer(&reqwest::Client::new(), &server.url(FILE), &path, body.len() as i64, &validator, &mut rx, &Throttle::unlimited(), None, |_| Ok(()))
+            .await
+            .unwrap_err();
+        assert!(error.transient);
+        assert_eq!(error.retry_after, Some(Duration::from_secs(1)));
+        assert_eq!(std::fs::read(&path).unwrap(), body[..1000]);
+
+        // Повтор продолжает с того же места
+        let outcome = run(&server, &path, body.len() as i64, &validator).await;
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].header("range"), Some("bytes=1000-"));
+    }
+
+    #[tokio::test]
+    async fn already_complete_file_is_not_downloaded_again() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            etag: Some("\"v1\"".to_string()),
+            honor_ranges: true,
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        std::fs::write(&path, &body).unwrap();
+        let validator = Validator {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let outcome = run(&server, &path, body.len() as i64, &validator).await;
+
+        assert_eq!(outcome.end, TransferEnd::Completed);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(outcome.digest, Some(sha256(&body)));
+    }
+
+    #[tokio::test]
+    async fn reports_size_before_body() {
+        let body = test_body();
+        let server = serve_file(FileConfig {
+            body: body.clone(),
+            ..Default::default()
+        })
+        .await;
+        let dir = TempDir::new("transfer");
+        let path = dir.join("file.bin");
+        let (_tx, mut rx) = watch::channel(Control::Run);
+
+        // Размер неизвестен заранее — обработчик узнаёт его из ответа и останавливает передачу
+        let mut reported = Vec::new();
+        let error = transfer(&reqwest::Client::new(), &server.url(FILE), &path, -1, &Validator::default(), &mut rx, &Throttle::unlimited(), None, |progress| {
+            reported.push((progress.received_bytes, progress.total_bytes));
+            Err(TransferError::too_large(progress.total_bytes, 1024))
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.too_large, Some(body.len() as i64));
+        assert_eq!(reported, vec![(0, body.len() as i64)]);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn weak_etag_falls_back_to_last_modified() {
+        let validator = Validator {
+            etag: Some("W/\"v1\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+        assert_eq!(validator.if_range(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+    }
+}
@@ -6,6 +6,8 @@ mod storage;
 mod downloads;
 mod webview_manager;
 mod scripts;
+#[cfg(test)]
+mod test_support;
 
 use tauri::Manager;
 use std::sync::Mutex;
@@ -13,10 +15,11 @@ use webview_manager::WebViewManager;
 
 pub struct AppState {
     pub frozen_tabs: Mutex<std::collections::HashSet<String>>,
-    pub downloads: Mutex<std::collections::HashMap<String, downloads::Download>>,
     pub download_manager: Mutex<downloads::DownloadManager>,
     pub webview_manager: Mutex<WebViewManager>,
     pub webview_bounds: Mutex<std::collections::HashMap<String, webview_manager::WebViewBounds>>,
+    /// Профиль, в котором приложение перезапустится после выхода
+    pub switch_to_profile: Mutex<Option<String>>,
 }
 
 
@@ -27,10 +30,10 @@ fn main() {
         .plugin(tauri_plugin_opener::init())
         .manage(AppState {
             frozen_tabs: Mutex::new(std::collections::HashSet::new()),
-            downloads: Mutex::new(std::collections::HashMap::new()),
             download_manager: Mutex::new(downloads::DownloadManager::new()),
             webview_manager: Mutex::new(WebViewManager::new()),
             webview_bounds: Mutex::new(std::collections::HashMap::new()),
+            switch_to_profile: Mutex::new(None),
         })
         .invoke_handler(tauri::generate_handler![
             // Window commands
@@ -45,11 +48,20 @@ fn main() {
             // Bookmarks
             commands::get_bookmarks,
             commands::set_bookmarks,
+            commands::create_bookmark_folder,
+            commands::move_bookmark,
+            commands::reorder_bookmarks,
+            commands::get_bookmarks_by_tag,
+            commands::resolve_keyword,
             // History
             commands::get_history,
             commands::add_history,
             commands::clear_history,
             commands::set_history,
+            // Search
+            commands::search_history,
+            commands::search_bookmarks,
+            commands::autocomplete,
             // External
             commands::open_external,
             commands::show_save_dialog,
@@ -66,10 +78,19 @@ fn main() {
             commands::cancel_download,
             commands::pause_download,
             commands::resume_download,
+            commands::resume_interrupted_downloads,
+            commands::take_interrupted_downloads,
+            commands::get_download_queue,
+            commands::move_download_in_queue,
+            commands::prioritize_download,
+            commands::set_download_speed_limit,
+            commands::compute_download_hash,
             commands::open_download,
+            commands::confirm_download,
             commands::show_download_in_folder,
             commands::clear_completed_downloads,
             commands::get_downloads_folder,
+            commands::pick_download_folder,
             // Import
             commands::import_from_browser,
             commands::detect_browsers,
@@ -79,6 +100,12 @@ fn main() {
             commands::save_session,
             commands::restore_session,
             commands::clear_session,
+            // Profiles
+            commands::list_profiles,
+            commands::create_profile,
+            commands::rename_profile,
+            commands::delete_profile,
+            commands::switch_profile,
             // WebView2 commands - lifecycle
             webview_manager::commands::lifecycle::create_webview,
             webview_manager::commands::lifecycle::close_webview,
@@ -104,14 +131,85 @@ fn main() {
             webview_manager::commands::misc::update_page_info,
         ])
         .setup(|app| {
+            // Выбираем профиль и обновляем формат его данных до того, как к ним обратятся команды
+            storage::select_profile(profile_arg().as_deref())?;
+            storage::migrate_storage()?;
+
+            // Загрузки, оборванные закрытием приложения, помечаем прерванными
+            let interrupted = tauri::async_runtime::block_on(downloads::reconcile_downloads())
+                .unwrap_or_default();
+            if let Ok(mut manager) = app.state::<AppState>().download_manager.lock() {
+                manager.interrupted_on_startup = interrupted;
+            }
+            // Удаляем брошенные недокачанные файлы, применяем лимит скорости
+            // и возвращаем в очередь ожидавшие загрузки
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = downloads::remove_stale_parts().await;
+                let _ = downloads::restore(&handle).await;
+            });
+            downloads::watch_settings(app.handle());
+
             let window = app.get_webview_window("main").unwrap();
             // Регистрируем горячие клавиши через JavaScript
             setup_keyboard_shortcuts(&window);
             
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Перезапуск в другом профиле — когда этот процесс уже всё сохранил
+            if let tauri::RunEvent::Exit = event {
+                let profile = app.state::<AppState>()
+                    .switch_to_profile
+                    .lock()
+                    .ok()
+                    .and_then(|mut profile| profile.take());
+                if let Some(profile) = profile {
+                    relaunch_in_profile(&profile);
+                }
+            }
+        });
+}
+
+/// Профиль из аргументов запуска: `--profile work` или `--profile=work`
+fn profile_arg() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix("--profile=") {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Запустить приложение заново в профиле `id` с теми же аргументами,
+/// заменив прежний `--profile`
+fn relaunch_in_profile(id: &str) {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    let mut args = Vec::new();
+    let mut original = std::env::args_os().skip(1);
+    while let Some(arg) = original.next() {
+        if arg == "--profile" {
+            original.next();
+            continue;
+        }
+        if arg.to_str().is_some_and(|arg| arg.starts_with("--profile=")) {
+            continue;
+        }
+        args.push(arg);
+    }
+    let _ = std::process::Command::new(exe)
+        .args(args)
+        .arg("--profile")
+        .arg(id)
+        .spawn();
 }
 
 fn setup_keyboard_shortcuts(window: &tauri::WebviewWindow) {
@@ -0,0 +1,185 @@
+//! Безопасная запись JSON-хранилищ
+//!
+//! Запись идёт во временный файл рядом с основным, после fsync он переименовывается
+//! поверх основного — основной файл не пропадает ни в какой момент записи.
+//! Предыдущая версия сохраняется в `<name>.bak`; если основной файл не читается
+//! (например, повреждён), используется резервная копия.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Сохранить текущую версию в `.bak`. Копия собирается во временном файле,
+/// поэтому и сама резервная копия никогда не бывает записана наполовину.
+fn backup(path: &Path) -> Result<(), String> {
+    let tmp = temp_path(&backup_path(path));
+    // Жёсткая ссылка ничего не копирует: после замены основного файла
+    // на ней остаётся старая версия. Если ФС ссылки не умеет — копируем
+    if std::fs::hard_link(path, &tmp).is_err() {
+        std::fs::copy(path, &tmp).map_err(|e| e.to_string())?;
+    }
+    std::fs::rename(&tmp, backup_path(path)).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        e.to_string()
+    })
+}
+
+/// Файл есть и содержит корректный JSON
+fn is_valid_json(path: &Path) -> bool {
+    std::fs::read(path)
+        .map(|content| serde_json::from_slice::<serde::de::IgnoredAny>(&content).is_ok())
+        .unwrap_or(false)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", &uuid::Uuid::new_v4().simple().to_string()[..8]));
+    path.with_file_name(name)
+}
+
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), String> {
+    let tmp = temp_path(path);
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp).map_err(|e| e.to_string())?;
+        file.write_all(content).map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())?;
+        drop(file);
+
+        // Текущая версия становится резервной, основной файл остаётся на месте.
+        // Повреждённая версия в резерв не попадает — иначе она заменила бы
+        // последнюю целую копию, из которой её и прочитали
+        if is_valid_json(path) {
+            backup(path)?;
+        }
+        std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+        return result;
+    }
+
+    // Фиксируем переименование на диске (на Windows каталог так открыть нельзя)
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+
+    Ok(())
+}
+
+fn read_parsed<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+/// Записать значение в JSON-файл атомарно (в текущем потоке)
+pub(super) fn write_json_blocking<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, &content)
+}
+
+/// Прочитать JSON-файл (в текущем потоке). Если основной файл повреждён или пропал,
+/// читается `.bak`. `None` — файла нет совсем.
+pub(super) fn read_json_blocking<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let backup = backup_path(path);
+
+    if path.exists() {
+        match read_parsed(path) {
+            Ok(value) => return Ok(Some(value)),
+            Err(primary_err) => {
+                if backup.exists() {
+                    if let Ok(value) = read_parsed(&backup) {
+                        return Ok(Some(value));
+                    }
+                }
+                return Err(primary_err);
+            }
+        }
+    }
+
+    // Основного файла нет (удалён вручную или пропал при сбое), но есть резервная копия
+    if backup.exists() {
+        return read_parsed(&backup).map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Записать значение в JSON-файл атомарно
+pub async fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    let path = path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || write_atomic(&path, &content))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Прочитать JSON-файл с откатом на `.bak`, см. [`read_json_blocking`]
+pub async fn read_json<T: DeserializeOwned + Send + 'static>(path: &Path) -> Result<Option<T>, String> {
+    let path = path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || read_json_blocking(&path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Удалить JSON-файл вместе с резервной копией (иначе он "воскреснет" из `.bak`)
+pub async fn remove_json(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        tokio::fs::remove_file(path)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let backup = backup_path(path);
+    if backup.exists() {
+        tokio::fs::remove_file(backup)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn keeps_previous_version_in_backup() {
+        let dir = TempDir::new("atomic");
+        let path = dir.join("settings.json");
+
+        write_json_blocking(&path, &1).unwrap();
+        assert!(!backup_path(&path).exists());
+        write_json_blocking(&path, &2).unwrap();
+        write_json_blocking(&path, &3).unwrap();
+
+        assert_eq!(read_parsed::<i32>(&path).unwrap(), 3);
+        assert_eq!(read_parsed::<i32>(&backup_path(&path)).unwrap(), 2);
+        // Временных файлов не остаётся
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        // Повреждённый основной файл читается из резервной копии
+        std::fs::write(&path, b"{").unwrap();
+        assert_eq!(read_json_blocking::<i32>(&path).unwrap(), Some(2));
+
+        // и не затирает её при следующей записи
+        write_json_blocking(&path, &4).unwrap();
+        assert_eq!(read_parsed::<i32>(&path).unwrap(), 4);
+        assert_eq!(read_parsed::<i32>(&backup_path(&path)).unwrap(), 2);
+    }
+}
@@ -0,0 +1,393 @@
+//! Подсказки адресной строки
+//!
+//! Источники: история, закладки, быстрые сайты и открытые вкладки.
+//! Ранжирование по frecency в духе Firefox: число посещений × вес давности,
+//! с бонусом для адресов, которые пользователь вводил вручную.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::history::with_history_db;
+use super::search::build_fts_query;
+use super::{get_bookmarks, BookmarkKind, Transition};
+
+const DEFAULT_LIMIT: u32 = 10;
+const HISTORY_CANDIDATES: i64 = 200;
+
+/// Сколько последних посещений учитывается при подсчёте frecency
+const FRECENCY_SAMPLE: i64 = 10;
+
+const TYPED_VISIT_BONUS: f64 = 2000.0;
+const LINK_VISIT_BONUS: f64 = 100.0;
+
+const BOOKMARK_BONUS: f64 = 140.0;
+const QUICK_SITE_BONUS: f64 = 120.0;
+const OPEN_TAB_BONUS: f64 = 100.0;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Источник подсказки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    OpenTab,
+    Bookmark,
+    QuickSite,
+    History,
+}
+
+impl MatchKind {
+    /// При совпадении адреса в нескольких источниках показываем самый полезный
+    fn priority(self) -> u8 {
+        match self {
+            MatchKind::OpenTab => 3,
+            MatchKind::Bookmark => 2,
+            MatchKind::QuickSite => 1,
+            MatchKind::History => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub kind: MatchKind,
+    pub url: String,
+    pub title: String,
+    pub favicon: Option<String>,
+    pub score: f64,
+    /// ID вкладки для подсказок "перейти на вкладку"
+    #[serde(rename = "tabId")]
+    pub tab_id: Option<String>,
+}
+
+/// Быстрый сайт (хранится во фронтенде, передаётся вместе с запросом)
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuickSite {
+    pub name: String,
+    pub url: String,
+}
+
+/// Открытая вкладка из WebViewManager
+#[derive(Debug, Clone)]
+pub struct OpenTab {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub favicon: Option<String>,
+}
+
+/// Строка без схемы и `www.` в начале
+fn strip_scheme(url: &str) -> &str {
+    let without_scheme = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    without_scheme.strip_prefix("www.").unwrap_or(without_scheme)
+}
+
+/// Адрес без схемы, `www.` и завершающего слэша — для сравнения и дедупликации
+fn normalize_url(url: &str) -> String {
+    let lower = url.trim().to_lowercase();
+    strip_scheme(&lower).trim_end_matches('/').to_string()
+}
+
+/// Насколько хорошо адрес/заголовок совпадают с вводом (None — не совпадают).
+/// Ввод сравнивается так же без схемы и `www.`, как и адрес
+fn match_quality(input: &str, url: &str, title: &str) -> Option<f64> {
+    let input = strip_scheme(input);
+    if input.is_empty() {
+        return None;
+    }
+    let normalized = normalize_url(url);
+    let title = title.to_lowercase();
+
+    // У normalized срезан завершающий слэш, а во вводе он может быть
+    if format!("{}/", normalized).starts_with(input) {
+        return Some(2.0);
+    }
+
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let all_match = words
+        .iter()
+        .all(|w| normalized.contains(w) || title.contains(w));
+    if !all_match {
+        return None;
+    }
+
+    let title_word_prefix = title
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|w| w.starts_with(words[0]));
+    if title_word_prefix {
+        Some(1.5)
+    } else {
+        Some(1.0)
+    }
+}
+
+/// Вес давности посещения (корзины как в Firefox)
+fn recency_weight(age_ms: i64) -> f64 {
+    match age_ms.max(0) / DAY_MS {
+        0..=4 => 100.0,
+        5..=14 => 70.0,
+        15..=31 => 50.0,
+        32..=90 => 30.0,
+        _ => 10.0,
+    }
+}
+
+/// Frecency адреса: число посещений × средний взвешенный бонус последних посещений
+fn frecency(conn: &Connection, url_id: i64, visit_count: i64, now: i64) -> Result<f64, String> {
+    let mut stmt = conn
+        .prepare_cached(
+            "SELECT visit_time, transition FROM visits WHERE url_id = ?1 ORDER BY visit_time DESC LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![url_id, FRECENCY_SAMPLE], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut points = 0.0;
+    let mut sampled = 0;
+    for row in rows {
+        let (visit_time, transition) = row.map_err(|e| e.to_string())?;
+        let bonus = match Transition::from_i64(transition) {
+            Transition::Typed => TYPED_VISIT_BONUS,
+            Transition::Link => LINK_VISIT_BONUS,
+        };
+        points += bonus / 100.0 * recency_weight(now - visit_time);
+        sampled += 1;
+    }
+
+    if sampled == 0 {
+        return Ok(0.0);
+    }
+
+    Ok(visit_count as f64 * points / sampled as f64)
+}
+
+/// Frecency для произвольного адреса (для закладок, вкладок и быстрых сайтов)
+fn frecency_for_url(conn: &Connection, url: &str, now: i64) -> Result<f64, String> {
+    let row: Option<(i64, i64)> = conn
+        .query_row(
+            "SELECT id, visit_count FROM urls WHERE url = ?1",
+            params![url],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+
+    match row {
+        Some((url_id, visit_count)) => frecency(conn, url_id, visit_count, now),
+        None => Ok(0.0),
+    }
+}
+
+fn history_suggestions(conn: &Connection, input: &str, now: i64) -> Result<Vec<Suggestion>, String> {
+    let fts_query = match build_fts_query(strip_scheme(input)) {
+        Some(q) => q,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "
+        SELECT u.id, u.url, u.title, u.favicon, u.visit_count
+        FROM urls_fts
+        JOIN urls u ON u.id = urls_fts.rowid
+        WHERE urls_fts MATCH ?1
+        ORDER BY u.visit_count DESC, u.last_visit_time DESC
+        LIMIT ?2
+    ",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![fts_query, HISTORY_CANDIDATES], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut suggestions = Vec::new();
+    for row in rows {
+        let (url_id, url, title, favicon, visit_count) = row.map_err(|e| e.to_string())?;
+        let quality = match match_quality(input, &url, &title) {
+            Some(q) => q,
+            None => continue,
+        };
+
+        suggestions.push(Suggestion {
+            kind: MatchKind::History,
+            score: frecency(conn, url_id, visit_count, now)? * quality,
+            url,
+            title,
+            favicon,
+            tab_id: None,
+        });
+    }
+
+    Ok(suggestions)
+}
+
+/// Подсказки для адресной строки, отсортированные по убыванию score
+pub async fn autocomplete(
+    input: String,
+    open_tabs: Vec<OpenTab>,
+    quick_sites: Vec<QuickSite>,
+    limit: Option<u32>,
+) -> Result<Vec<Suggestion>, String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let bookmarks = get_bookmarks().await?;
+
+    with_history_db(move |conn| {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut suggestions = history_suggestions(conn, &input, now)?;
+
+        for bookmark in bookmarks.iter().filter(|b| b.kind == BookmarkKind::Bookmark) {
+            if let Some(quality) = match_quality(&input, &bookmark.url, &bookmark.title) {
+                let score = (frecency_for_url(conn, &bookmark.url, now)? + BOOKMARK_BONUS) * quality;
+                suggestions.push(Suggestion {
+                    kind: MatchKind::Bookmark,
+                    url: bookmark.url.clone(),
+                    title: bookmark.title.clone(),
+                    favicon: bookmark.favicon.clone(),
+                    score,
+                    tab_id: None,
+                });
+            }
+        }
+
+        for site in &quick_sites {
+            if let Some(quality) = match_quality(&input, &site.url, &site.name) {
+                let score = (frecency_for_url(conn, &site.url, now)? + QUICK_SITE_BONUS) * quality;
+                suggestions.push(Suggestion {
+                    kind: MatchKind::QuickSite,
+                    url: site.url.clone(),
+                    title: site.name.clone(),
+                    favicon: None,
+                    score,
+                    tab_id: None,
+                });
+            }
+        }
+
+        for tab in &open_tabs {
+            if let Some(quality) = match_quality(&input, &tab.url, &tab.title) {
+                let score = (frecency_for_url(conn, &tab.url, now)? + OPEN_TAB_BONUS) * quality;
+                suggestions.push(Suggestion {
+                    kind: MatchKind::OpenTab,
+                    url: tab.url.clone(),
+                    title: tab.title.clone(),
+                    favicon: tab.favicon.clone(),
+                    score,
+                    tab_id: Some(tab.id.clone()),
+                });
+            }
+        }
+
+        // Один адрес — одна подсказка: лучший score и самый полезный источник
+        let mut merged: HashMap<String, Suggestion> = HashMap::new();
+        for suggestion in suggestions {
+            let key = normalize_url(&suggestion.url);
+            match merged.get_mut(&key) {
+                Some(existing) => {
+                    let score = existing.score.max(suggestion.score);
+                    if suggestion.kind.priority() > existing.kind.priority() {
+                        *existing = suggestion;
+                    }
+                    existing.score = score;
+                }
+                None => {
+                    merged.insert(key, suggestion);
+                }
+            }
+        }
+
+        let mut result: Vec<Suggestion> = merged.into_values().collect();
+        result.sort_by(|a, b| b.score.total_cmp(&a.score));
+        result.truncate(limit);
+
+        Ok(result)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::history::{insert_visit, open_history_db};
+    use crate::storage::HistoryEntry;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn typed_visits_outrank_link_visits() {
+        let dir = TempDir::new("autocomplete");
+        let mut conn = open_history_db(&dir).unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        // Посещения в том виде, в каком их присылает фронтенд
+        let visits = [
+            ("v1", "https://rustfmt.example/", "typed"),
+            ("v2", "https://rustup.example/", "link"),
+            ("v3", "https://rustup.example/", "link"),
+            ("v4", "https://rustup.example/", "link"),
+        ];
+        let tx = conn.transaction().unwrap();
+        for (id, url, transition) in visits {
+            let entry: HistoryEntry = serde_json::from_value(serde_json::json!({
+                "id": id,
+                "url": url,
+                "title": "",
+                "favicon": null,
+                "visitedAt": now,
+                "transition": transition,
+            }))
+            .unwrap();
+            insert_visit(&tx, &entry).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let stored: i64 = conn
+            .query_row("SELECT transition FROM visits WHERE id = 'v1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(Transition::from_i64(stored), Transition::Typed);
+
+        // Один введённый вручную адрес выше трёх переходов по ссылке
+        let mut suggestions = history_suggestions(&conn, "rust", now).unwrap();
+        suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+        let urls: Vec<&str> = suggestions.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://rustfmt.example/", "https://rustup.example/"]);
+
+        // Схема и `www.` во вводе не мешают совпадению по префиксу
+        let suggestions = history_suggestions(&conn, "https://www.rustup", now).unwrap();
+        let urls: Vec<&str> = suggestions.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://rustup.example/"]);
+    }
+
+    #[test]
+    fn ignores_scheme_and_www_in_input() {
+        let cases = [
+            ("https://git", "https://github.com/", Some(2.0)),
+            ("http://github.com/", "https://github.com/", Some(2.0)),
+            ("www.goo", "https://www.google.com/", Some(2.0)),
+            ("https://www.rust", "https://rust-lang.org/", Some(2.0)),
+            ("https://", "https://github.com/", None),
+            ("https://gitlab", "https://github.com/", None),
+        ];
+        for (input, url, expected) in cases {
+            assert_eq!(match_quality(input, url, ""), expected, "{}", input);
+        }
+    }
+}
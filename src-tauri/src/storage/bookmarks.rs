@@ -1,24 +1,217 @@
-use super::{ensure_data_dir, Bookmark};
+use super::atomic::{read_json, write_json};
+use super::{ensure_data_dir, search, Bookmark, BookmarkKind};
 
 pub async fn get_bookmarks() -> Result<Vec<Bookmark>, String> {
     let path = ensure_data_dir()?.join("bookmarks.json");
 
-    if !path.exists() {
-        return Ok(Vec::new());
+    Ok(read_json(&path).await?.unwrap_or_default())
+}
+
+pub async fn set_bookmarks(bookmarks: Vec<Bookmark>) -> Result<(), String> {
+    let path = ensure_data_dir()?.join("bookmarks.json");
+    write_json(&path, &bookmarks).await?;
+
+    // Закладки уже сохранены; отстающий индекс поиск перестроит сам
+    if let Err(e) = search::reindex_bookmarks(bookmarks).await {
+        eprintln!("Failed to reindex bookmarks: {}", e);
     }
+    Ok(())
+}
 
-    let content = tokio::fs::read_to_string(path)
-        .await
-        .map_err(|e| e.to_string())?;
+/// Индексы дочерних элементов папки в порядке `position`
+/// (при равных позициях сохраняется порядок в файле — так выглядят старые плоские списки)
+pub(super) fn children_of(bookmarks: &[Bookmark], parent_id: Option<&str>) -> Vec<usize> {
+    let mut children: Vec<usize> = bookmarks
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.parent_id.as_deref() == parent_id)
+        .map(|(i, _)| i)
+        .collect();
+    children.sort_by_key(|&i| bookmarks[i].position);
+    children
+}
 
-    serde_json::from_str(&content).map_err(|e| e.to_string())
+/// Записать позиции 0..n в заданном порядке
+fn apply_order(bookmarks: &mut [Bookmark], order: &[usize]) {
+    for (position, &i) in order.iter().enumerate() {
+        bookmarks[i].position = position as i64;
+    }
 }
 
-pub async fn set_bookmarks(bookmarks: Vec<Bookmark>) -> Result<(), String> {
-    let path = ensure_data_dir()?.join("bookmarks.json");
-    let content = serde_json::to_string_pretty(&bookmarks).map_err(|e| e.to_string())?;
+/// Лежит ли `id` внутри папки `ancestor_id` (на любой глубине)
+fn is_inside(bookmarks: &[Bookmark], id: &str, ancestor_id: &str) -> bool {
+    let mut current = bookmarks
+        .iter()
+        .find(|b| b.id == id)
+        .and_then(|b| b.parent_id.clone());
+
+    // Ограничиваем глубину на случай зацикленных данных
+    for _ in 0..bookmarks.len() {
+        match current {
+            Some(ref parent) if parent == ancestor_id => return true,
+            Some(ref parent) => {
+                current = bookmarks
+                    .iter()
+                    .find(|b| &b.id == parent)
+                    .and_then(|b| b.parent_id.clone());
+            }
+            None => return false,
+        }
+    }
+
+    false
+}
+
+fn ensure_folder(bookmarks: &[Bookmark], folder_id: Option<&str>) -> Result<(), String> {
+    match folder_id {
+        None => Ok(()),
+        Some(id) => match bookmarks.iter().find(|b| b.id == id) {
+            Some(b) if b.kind == BookmarkKind::Folder => Ok(()),
+            Some(_) => Err(format!("Bookmark {} is not a folder", id)),
+            None => Err(format!("Folder {} not found", id)),
+        },
+    }
+}
+
+/// Создать папку в конце указанной папки
+pub async fn create_bookmark_folder(
+    title: String,
+    parent_id: Option<String>,
+) -> Result<Bookmark, String> {
+    let mut bookmarks = get_bookmarks().await?;
+    ensure_folder(&bookmarks, parent_id.as_deref())?;
+
+    let position = children_of(&bookmarks, parent_id.as_deref()).len() as i64;
+    let folder = Bookmark {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: String::new(),
+        title,
+        favicon: None,
+        created_at: chrono::Utc::now().timestamp_millis(),
+        kind: BookmarkKind::Folder,
+        parent_id,
+        position,
+        tags: Default::default(),
+        keyword: None,
+    };
+
+    bookmarks.push(folder.clone());
+    set_bookmarks(bookmarks).await?;
+
+    Ok(folder)
+}
+
+/// Переместить закладку/папку в другую папку (или на верхний уровень).
+/// `position` — место среди новых соседей, по умолчанию в конец.
+pub async fn move_bookmark(
+    id: String,
+    parent_id: Option<String>,
+    position: Option<usize>,
+) -> Result<Vec<Bookmark>, String> {
+    let mut bookmarks = get_bookmarks().await?;
+    ensure_folder(&bookmarks, parent_id.as_deref())?;
+
+    let index = bookmarks
+        .iter()
+        .position(|b| b.id == id)
+        .ok_or_else(|| format!("Bookmark {} not found", id))?;
+
+    if let Some(ref target) = parent_id {
+        if *target == id || is_inside(&bookmarks, target, &id) {
+            return Err("Cannot move a folder into itself".to_string());
+        }
+    }
+
+    let old_parent = bookmarks[index].parent_id.clone();
+
+    // Закрываем дыру на старом месте
+    let old_siblings: Vec<usize> = children_of(&bookmarks, old_parent.as_deref())
+        .into_iter()
+        .filter(|&i| i != index)
+        .collect();
+    apply_order(&mut bookmarks, &old_siblings);
+
+    // Вставляем среди новых соседей
+    let mut new_siblings: Vec<usize> = children_of(&bookmarks, parent_id.as_deref())
+        .into_iter()
+        .filter(|&i| i != index)
+        .collect();
+    let position = position.unwrap_or(new_siblings.len()).min(new_siblings.len());
+    new_siblings.insert(position, index);
+
+    bookmarks[index].parent_id = parent_id;
+    apply_order(&mut bookmarks, &new_siblings);
+
+    set_bookmarks(bookmarks.clone()).await?;
+    Ok(bookmarks)
+}
+
+/// Задать порядок элементов папки. Элементы, которых нет в `ordered_ids`,
+/// остаются после перечисленных в прежнем порядке.
+pub async fn reorder_bookmarks(
+    parent_id: Option<String>,
+    ordered_ids: Vec<String>,
+) -> Result<Vec<Bookmark>, String> {
+    let mut bookmarks = get_bookmarks().await?;
+    ensure_folder(&bookmarks, parent_id.as_deref())?;
+
+    let children = children_of(&bookmarks, parent_id.as_deref());
+
+    let mut order: Vec<usize> = Vec::with_capacity(children.len());
+    for id in &ordered_ids {
+        match children.iter().find(|&&i| bookmarks[i].id == *id) {
+            Some(&i) if !order.contains(&i) => order.push(i),
+            Some(_) => {}
+            None => return Err(format!("Bookmark {} is not in this folder", id)),
+        }
+    }
+    for &i in &children {
+        if !order.contains(&i) {
+            order.push(i);
+        }
+    }
+
+    apply_order(&mut bookmarks, &order);
+
+    set_bookmarks(bookmarks.clone()).await?;
+    Ok(bookmarks)
+}
+
+/// Закладки с указанным тегом (без учёта регистра)
+pub async fn get_bookmarks_by_tag(tag: String) -> Result<Vec<Bookmark>, String> {
+    let tag = tag.trim().to_lowercase();
+    let bookmarks = get_bookmarks().await?;
+
+    Ok(bookmarks
+        .into_iter()
+        .filter(|b| b.tags.iter().any(|t| t.to_lowercase() == tag))
+        .collect())
+}
+
+/// Подставить запрос в URL закладки: `%s` — с кодированием, `%S` — как есть
+fn expand_keyword_url(url: &str, query: &str) -> String {
+    url.replace("%s", &urlencoding::encode(query))
+        .replace("%S", query)
+}
+
+/// Развернуть ввод вида `<keyword> [запрос]` в URL закладки с этим ключевым словом
+pub async fn resolve_keyword(input: String) -> Result<Option<String>, String> {
+    let input = input.trim();
+    let (keyword, query) = match input.split_once(char::is_whitespace) {
+        Some((keyword, query)) => (keyword, query.trim()),
+        None => (input, ""),
+    };
+    if keyword.is_empty() {
+        return Ok(None);
+    }
+
+    let bookmarks = get_bookmarks().await?;
+    let bookmark = bookmarks.iter().find(|b| {
+        b.kind == BookmarkKind::Bookmark
+            && b.keyword
+                .as_deref()
+                .is_some_and(|k| k.eq_ignore_ascii_case(keyword))
+    });
 
-    tokio::fs::write(path, content)
-        .await
-        .map_err(|e| e.to_string())
+    Ok(bookmark.map(|b| expand_keyword_url(&b.url, query)))
 }
@@ -1,53 +1,333 @@
-use super::{ensure_data_dir, HistoryEntry};
+//! История посещений в SQLite
+//!
+//! `urls` хранит по одной строке на адрес (заголовок, favicon, счётчик посещений),
+//! `visits` — каждое отдельное посещение. Старый `history.json` переносится
+//! в базу миграцией хранилища (см. `migrations.rs`).
 
-pub async fn get_history() -> Result<Vec<HistoryEntry>, String> {
-    let path = ensure_data_dir()?.join("history.json");
+use rusqlite::{params, Connection, Transaction};
+use std::path::Path;
+
+use super::{ensure_data_dir, HistoryEntry, Transition};
+
+const HISTORY_DB: &str = "history.db";
+const LEGACY_HISTORY_JSON: &str = "history.json";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS urls (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        url TEXT NOT NULL UNIQUE,
+        title TEXT NOT NULL DEFAULT '',
+        favicon TEXT,
+        visit_count INTEGER NOT NULL DEFAULT 0,
+        last_visit_time INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TABLE IF NOT EXISTS visits (
+        id TEXT PRIMARY KEY,
+        url_id INTEGER NOT NULL REFERENCES urls(id) ON DELETE CASCADE,
+        visit_time INTEGER NOT NULL,
+        transition INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS visits_time_idx ON visits(visit_time DESC);
+    CREATE INDEX IF NOT EXISTS visits_url_idx ON visits(url_id);
+    CREATE INDEX IF NOT EXISTS urls_last_visit_idx ON urls(last_visit_time DESC);
+";
 
-    if !path.exists() {
-        return Ok(Vec::new());
+/// Полнотекстовый индекс по адресу и заголовку (external content поверх `urls`)
+const FTS_SCHEMA: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS urls_fts USING fts5(
+        url, title,
+        content = 'urls', content_rowid = 'id',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER IF NOT EXISTS urls_fts_ai AFTER INSERT ON urls BEGIN
+        INSERT INTO urls_fts (rowid, url, title) VALUES (new.id, new.url, new.title);
+    END;
+    CREATE TRIGGER IF NOT EXISTS urls_fts_ad AFTER DELETE ON urls BEGIN
+        INSERT INTO urls_fts (urls_fts, rowid, url, title) VALUES ('delete', old.id, old.url, old.title);
+    END;
+    CREATE TRIGGER IF NOT EXISTS urls_fts_au AFTER UPDATE OF url, title ON urls BEGIN
+        INSERT INTO urls_fts (urls_fts, rowid, url, title) VALUES ('delete', old.id, old.url, old.title);
+        INSERT INTO urls_fts (rowid, url, title) VALUES (new.id, new.url, new.title);
+    END;
+";
+
+/// Открыть базу истории и создать/обновить схему
+pub(super) fn open_history_db(data_dir: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(data_dir.join(HISTORY_DB)).map_err(|e| e.to_string())?;
+
+    conn.execute_batch(
+        "
+        PRAGMA journal_mode = WAL;
+        PRAGMA synchronous = NORMAL;
+        PRAGMA foreign_keys = ON;
+        PRAGMA secure_delete = ON;
+    ",
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+
+    // Колонка transition появилась вместе с автодополнением
+    let has_transition: bool = conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info('visits') WHERE name = 'transition')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if !has_transition {
+        conn.execute(
+            "ALTER TABLE visits ADD COLUMN transition INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
     }
 
-    let content = tokio::fs::read_to_string(path)
-        .await
+    // База могла быть создана до появления полнотекстового индекса — заполняем его один раз
+    let has_fts: bool = conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'urls_fts')",
+            [],
+            |row| row.get(0),
+        )
         .map_err(|e| e.to_string())?;
+    conn.execute_batch(FTS_SCHEMA).map_err(|e| e.to_string())?;
+    if !has_fts {
+        conn.execute("INSERT INTO urls_fts (urls_fts) VALUES ('rebuild')", [])
+            .map_err(|e| e.to_string())?;
+    }
 
-    serde_json::from_str(&content).map_err(|e| e.to_string())
+    Ok(conn)
 }
 
-pub async fn add_history(entry: HistoryEntry) -> Result<(), String> {
-    let mut history = get_history().await?;
-    history.insert(0, entry);
-
-    // Ограничиваем до 5000 записей
-    if history.len() > 5000 {
-        history.truncate(5000);
+/// Перенос истории из `history.json` (формат до SQLite)
+pub(super) fn migrate_legacy_json(data_dir: &Path) -> Result<(), String> {
+    let legacy_path = data_dir.join(LEGACY_HISTORY_JSON);
+    if !legacy_path.exists() {
+        return Ok(());
     }
 
-    let path = ensure_data_dir()?.join("history.json");
-    let content = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
+    let mut conn = open_history_db(data_dir)?;
+
+    let content = std::fs::read_to_string(&legacy_path).map_err(|e| e.to_string())?;
+    let entries: Vec<HistoryEntry> = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for entry in &entries {
+        insert_visit(&tx, entry)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
 
-    tokio::fs::write(path, content)
-        .await
+    // Оставляем копию старого файла, но больше его не читаем
+    std::fs::rename(&legacy_path, legacy_path.with_extension("json.migrated"))
         .map_err(|e| e.to_string())
 }
 
-pub async fn clear_history() -> Result<(), String> {
-    let path = ensure_data_dir()?.join("history.json");
+/// Записать одно посещение: обновить строку в `urls` и добавить строку в `visits`
+pub(super) fn insert_visit(tx: &Transaction, entry: &HistoryEntry) -> Result<(), String> {
+    tx.execute(
+        "
+        INSERT INTO urls (url, title, favicon, visit_count, last_visit_time)
+        VALUES (?1, ?2, ?3, 0, ?4)
+        ON CONFLICT(url) DO UPDATE SET
+            title = CASE WHEN excluded.title <> '' AND excluded.last_visit_time >= last_visit_time
+                         THEN excluded.title ELSE title END,
+            favicon = COALESCE(excluded.favicon, favicon),
+            last_visit_time = MAX(last_visit_time, excluded.last_visit_time)
+    ",
+        params![entry.url, entry.title, entry.favicon, entry.visited_at],
+    )
+    .map_err(|e| e.to_string())?;
 
-    if path.exists() {
-        tokio::fs::remove_file(path)
-            .await
-            .map_err(|e| e.to_string())?;
+    let url_id: i64 = tx
+        .query_row("SELECT id FROM urls WHERE url = ?1", params![entry.url], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let inserted = tx
+        .execute(
+            "INSERT OR IGNORE INTO visits (id, url_id, visit_time, transition) VALUES (?1, ?2, ?3, ?4)",
+            params![entry.id, url_id, entry.visited_at, entry.transition.as_i64()],
+        )
+        .map_err(|e| e.to_string())?;
+
+    // Повторная запись того же посещения не должна увеличивать счётчик
+    if inserted > 0 {
+        tx.execute(
+            "UPDATE urls SET visit_count = visit_count + 1 WHERE id = ?1",
+            params![url_id],
+        )
+        .map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
 
+/// Заменить всю историю на `history`, не оставив удалённого в файлах базы:
+/// `secure_delete` затирает освободившиеся страницы, перестроенный индекс FTS
+/// избавляется от старых сегментов, а журнал WAL переносится в базу и обрезается
+fn replace_history(conn: &mut Connection, history: &[HistoryEntry]) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute_batch("DELETE FROM visits; DELETE FROM urls;")
+        .map_err(|e| e.to_string())?;
+    for entry in history {
+        insert_visit(&tx, entry)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    conn.execute_batch(
+        "
+        INSERT INTO urls_fts (urls_fts) VALUES ('rebuild');
+        PRAGMA wal_checkpoint(TRUNCATE);
+    ",
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn read_history(conn: &Connection) -> Result<Vec<HistoryEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "
+        SELECT v.id, u.url, u.title, u.favicon, v.visit_time, u.visit_count, v.transition
+        FROM visits v
+        JOIN urls u ON u.id = v.url_id
+        ORDER BY v.visit_time DESC
+    ",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(HistoryEntry {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                title: row.get(2)?,
+                favicon: row.get(3)?,
+                visited_at: row.get(4)?,
+                visit_count: row.get(5)?,
+                transition: Transition::from_i64(row.get(6)?),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut history = Vec::new();
+    for row in rows {
+        history.push(row.map_err(|e| e.to_string())?);
+    }
+
+    Ok(history)
+}
+
+/// Выполнить операцию с базой истории в блокирующем потоке
+pub(super) async fn with_history_db<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut Connection) -> Result<T, String> + Send + 'static,
+{
+    let data_dir = ensure_data_dir()?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut conn = open_history_db(&data_dir)?;
+        f(&mut conn)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub async fn get_history() -> Result<Vec<HistoryEntry>, String> {
+    with_history_db(|conn| read_history(conn)).await
+}
+
+pub async fn add_history(entry: HistoryEntry) -> Result<(), String> {
+    with_history_db(move |conn| {
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        insert_visit(&tx, &entry)?;
+        tx.commit().map_err(|e| e.to_string())
+    })
+    .await
+}
+
+pub async fn clear_history() -> Result<(), String> {
+    with_history_db(|conn| replace_history(conn, &[])).await
+}
+
 pub async fn set_history(history: Vec<HistoryEntry>) -> Result<(), String> {
-    let path = ensure_data_dir()?.join("history.json");
-    let content = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
+    with_history_db(move |conn| replace_history(conn, &history)).await
+}
 
-    tokio::fs::write(path, content)
-        .await
-        .map_err(|e| e.to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn entry(id: &str, url: &str, title: &str, favicon: Option<&str>, visited_at: i64) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            url: url.to_string(),
+            title: title.to_string(),
+            favicon: favicon.map(str::to_string),
+            visited_at,
+            visit_count: 1,
+            transition: Transition::Link,
+        }
+    }
+
+    fn insert(conn: &mut Connection, entry: &HistoryEntry) {
+        let tx = conn.transaction().unwrap();
+        insert_visit(&tx, entry).unwrap();
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn counts_visits_per_url() {
+        let dir = TempDir::new("history");
+        let mut conn = open_history_db(&dir).unwrap();
+        let url = "https://example.com/";
+
+        insert(&mut conn, &entry("v1", url, "First", Some("icon-1"), 1000));
+        insert(&mut conn, &entry("v2", url, "Second", None, 3000));
+        // Повторная запись посещения не считается новым посещением
+        insert(&mut conn, &entry("v2", url, "Second", None, 3000));
+        // Более старое посещение не меняет заголовок, пустой заголовок — тоже
+        insert(&mut conn, &entry("v3", url, "Older", None, 2000));
+        insert(&mut conn, &entry("v4", url, "", Some("icon-2"), 4000));
+
+        let history = read_history(&conn).unwrap();
+        let ids: Vec<&str> = history.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["v4", "v2", "v3", "v1"]);
+        for visit in &history {
+            assert_eq!(visit.url, url);
+            assert_eq!(visit.title, "Second");
+            assert_eq!(visit.favicon.as_deref(), Some("icon-2"));
+            assert_eq!(visit.visit_count, 4);
+        }
+
+        let last_visit: i64 = conn
+            .query_row("SELECT last_visit_time FROM urls", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(last_visit, 4000);
+    }
+
+    #[test]
+    fn removed_history_leaves_no_traces() {
+        let dir = TempDir::new("history");
+        let mut conn = open_history_db(&dir).unwrap();
+        // Столько, чтобы записи и индекс заняли много страниц
+        let secret = "zqxsecret";
+        for i in 0..500 {
+            let url = format!("https://{}{}.example/", secret, i);
+            insert(&mut conn, &entry(&format!("v{}", i), &url, &format!("{} page {}", secret, i), None, i));
+        }
+        let kept = entry("kept", "https://kept.example/", "Kept", None, 1000);
+        insert(&mut conn, &kept);
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);").unwrap();
+
+        replace_history(&mut conn, &[kept]).unwrap();
+        let ids: Vec<String> = read_history(&conn).unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["kept"]);
+
+        // Ни в базе, ни в журнале не осталось удалённых адресов и заголовков
+        for file in std::fs::read_dir(&dir).unwrap() {
+            let content = std::fs::read(file.unwrap().path()).unwrap();
+            assert!(!content.windows(secret.len()).any(|w| w == secret.as_bytes()));
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
 
-use super::{Bookmark, HistoryEntry};
+use super::{Bookmark, BookmarkKind, HistoryEntry, Transition};
 
 #[derive(Serialize)]
 pub struct ImportResult {
@@ -193,38 +194,22 @@ async fn import_chrome_based_browser(browser: &str) -> Result<Option<ImportResul
 
         let data: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
 
-        fn extract_bookmarks(node: &serde_json::Value, bookmarks: &mut Vec<Bookmark>) {
-            if let Some(node_type) = node.get("type").and_then(|v| v.as_str()) {
-                if node_type == "url" {
-                    if let (Some(url), Some(name)) = (
-                        node.get("url").and_then(|v| v.as_str()),
-                        node.get("name").and_then(|v| v.as_str()),
-                    ) {
-                        bookmarks.push(Bookmark {
-                            id: format!(
-                                "{}{}",
-                                chrono::Utc::now().timestamp_millis(),
-                                rand::random::<u32>()
-                            ),
-                            url: url.to_string(),
-                            title: name.to_string(),
-                            favicon: None,
-                            created_at: chrono::Utc::now().timestamp_millis(),
-                        });
-                    }
-                }
-            }
-
-            if let Some(children) = node.get("children").and_then(|v| v.as_array()) {
-                for child in children {
-                    extract_bookmarks(child, bookmarks);
-                }
-            }
-        }
-
+        // Сохраняем структуру корней: панель закладок, другие закладки, мобильные
         if let Some(roots) = data.get("roots").and_then(|v| v.as_object()) {
-            for (_, root) in roots {
-                extract_bookmarks(root, &mut bookmarks);
+            let mut position = 0;
+            for key in ["bookmark_bar", "other", "synced"] {
+                let root = match roots.get(key) {
+                    Some(root) => root,
+                    None => continue,
+                };
+                let has_children = root
+                    .get("children")
+                    .and_then(|v| v.as_array())
+                    .is_some_and(|c| !c.is_empty());
+                if has_children {
+                    extract_chrome_bookmarks(root, None, position, &mut bookmarks);
+                    position += 1;
+                }
             }
         }
     }
@@ -281,6 +266,8 @@ async fn import_chrome_history(history_path: &std::path::Path) -> Result<Vec<His
                     title,
                     favicon: None,
                     visited_at: chrome_time_to_timestamp(last_visit_time),
+                    visit_count: 1,
+                    transition: Transition::Link,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -307,6 +294,73 @@ fn chrome_time_to_timestamp(chrome_time: i64) -> i64 {
     (chrome_time - WEBKIT_TIMESTAMP_TO_UNIX_EPOCH) / 1000000
 }
 
+/// ID для импортированных записей
+fn new_import_id() -> String {
+    format!(
+        "{}{}",
+        chrono::Utc::now().timestamp_millis(),
+        rand::random::<u32>()
+    )
+}
+
+/// Рекурсивный обход узла из файла Bookmarks (Chrome/Edge) с сохранением папок
+fn extract_chrome_bookmarks(
+    node: &serde_json::Value,
+    parent_id: Option<&str>,
+    position: i64,
+    bookmarks: &mut Vec<Bookmark>,
+) {
+    let name = node.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+    // date_added — микросекунды с 1601-01-01 в виде строки
+    let created_at = node
+        .get("date_added")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<i64>().ok())
+        .map(|t| chrome_time_to_timestamp(t) * 1000)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+
+    match node.get("type").and_then(|v| v.as_str()) {
+        Some("url") => {
+            if let Some(url) = node.get("url").and_then(|v| v.as_str()) {
+                bookmarks.push(Bookmark {
+                    id: new_import_id(),
+                    url: url.to_string(),
+                    title: name.to_string(),
+                    favicon: None,
+                    created_at,
+                    kind: BookmarkKind::Bookmark,
+                    parent_id: parent_id.map(|s| s.to_string()),
+                    position,
+                    tags: Default::default(),
+                    keyword: None,
+                });
+            }
+        }
+        Some("folder") => {
+            let folder_id = new_import_id();
+            bookmarks.push(Bookmark {
+                id: folder_id.clone(),
+                url: String::new(),
+                title: name.to_string(),
+                favicon: None,
+                created_at,
+                kind: BookmarkKind::Folder,
+                parent_id: parent_id.map(|s| s.to_string()),
+                position,
+                tags: Default::default(),
+                keyword: None,
+            });
+
+            if let Some(children) = node.get("children").and_then(|v| v.as_array()) {
+                for (i, child) in children.iter().enumerate() {
+                    extract_chrome_bookmarks(child, Some(&folder_id), i as i64, bookmarks);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 async fn import_firefox_browser() -> Result<Option<ImportResult>, String> {
     let home_dir = dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
 
@@ -421,42 +475,41 @@ async fn import_firefox_data(
     let result = tokio::task::spawn_blocking(move || {
         let conn = rusqlite::Connection::open(&temp_path_clone).map_err(|e| e.to_string())?;
 
-        // Импорт закладок
-        let mut bookmarks = Vec::new();
+        // Импорт закладок вместе с папками и разделителями
         let mut stmt = conn
             .prepare(
                 "
-            SELECT b.title, p.url 
+            SELECT b.id, b.type, b.parent, b.position, b.title, p.url, b.dateAdded, b.guid, b.fk,
+                   (SELECT k.keyword FROM moz_keywords k WHERE k.place_id = b.fk LIMIT 1)
             FROM moz_bookmarks b
-            JOIN moz_places p ON b.fk = p.id
-            WHERE b.type = 1 AND p.url IS NOT NULL
-            LIMIT 1000
+            LEFT JOIN moz_places p ON b.fk = p.id
+            ORDER BY b.parent, b.position
         ",
             )
             .map_err(|e| e.to_string())?;
 
         let bookmark_rows = stmt
             .query_map([], |row| {
-                let title: String = row.get(0).unwrap_or_else(|_| "Untitled".to_string());
-                let url: String = row.get(1)?;
-
-                Ok(Bookmark {
-                    id: format!(
-                        "{}{}",
-                        chrono::Utc::now().timestamp_millis(),
-                        rand::random::<u32>()
-                    ),
-                    url,
-                    title,
-                    favicon: None,
-                    created_at: chrono::Utc::now().timestamp_millis(),
+                Ok(PlacesBookmark {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    parent: row.get(2)?,
+                    position: row.get(3)?,
+                    title: row.get(4)?,
+                    url: row.get(5)?,
+                    date_added: row.get(6)?,
+                    guid: row.get(7)?,
+                    place_id: row.get(8)?,
+                    keyword: row.get(9)?,
                 })
             })
             .map_err(|e| e.to_string())?;
 
+        let mut places_bookmarks = Vec::new();
         for row in bookmark_rows {
-            bookmarks.push(row.map_err(|e| e.to_string())?);
+            places_bookmarks.push(row.map_err(|e| e.to_string())?);
         }
+        let bookmarks = build_firefox_bookmarks(&places_bookmarks);
 
         // Импорт истории
         let mut history = Vec::new();
@@ -488,6 +541,8 @@ async fn import_firefox_data(
                     title,
                     favicon: None,
                     visited_at: firefox_time_to_timestamp(last_visit_date),
+                    visit_count: 1,
+                    transition: Transition::Link,
                 })
             })
             .map_err(|e| e.to_string())?;
@@ -507,6 +562,150 @@ async fn import_firefox_data(
     Ok(result)
 }
 
+/// Строка из moz_bookmarks
+struct PlacesBookmark {
+    id: i64,
+    kind: i64,
+    parent: i64,
+    position: i64,
+    title: Option<String>,
+    url: Option<String>,
+    date_added: Option<i64>,
+    guid: Option<String>,
+    place_id: Option<i64>,
+    keyword: Option<String>,
+}
+
+// Типы записей в moz_bookmarks
+const PLACES_TYPE_BOOKMARK: i64 = 1;
+const PLACES_TYPE_FOLDER: i64 = 2;
+const PLACES_TYPE_SEPARATOR: i64 = 3;
+
+/// Названия корневых папок Firefox (в places.sqlite они обычно пустые)
+fn firefox_root_title(guid: &str) -> Option<&'static str> {
+    match guid {
+        "toolbar_____" => Some("Bookmarks Toolbar"),
+        "menu________" => Some("Bookmarks Menu"),
+        "unfiled_____" => Some("Other Bookmarks"),
+        "mobile______" => Some("Mobile Bookmarks"),
+        _ => None,
+    }
+}
+
+/// Дерево moz_bookmarks, разобранное по родителям, и теги по place id
+struct FirefoxTree<'a> {
+    children: HashMap<i64, Vec<&'a PlacesBookmark>>,
+    tags: HashMap<i64, BTreeSet<String>>,
+}
+
+/// Собрать дерево закладок из строк moz_bookmarks.
+/// Корневые папки (панель, меню, другие, мобильные) становятся папками верхнего уровня.
+/// Папка тегов не импортируется как папка: её подпапки — это теги закладок.
+fn build_firefox_bookmarks(rows: &[PlacesBookmark]) -> Vec<Bookmark> {
+    let mut children: HashMap<i64, Vec<&PlacesBookmark>> = HashMap::new();
+    for row in rows {
+        children.entry(row.parent).or_default().push(row);
+    }
+
+    let root_id = rows
+        .iter()
+        .find(|r| r.guid.as_deref() == Some("root________"))
+        .map(|r| r.id)
+        .unwrap_or(1);
+
+    // tags________ → <папка с именем тега> → записи, ссылающиеся на тот же place
+    let mut tags: HashMap<i64, BTreeSet<String>> = HashMap::new();
+    if let Some(tags_root) = rows.iter().find(|r| r.guid.as_deref() == Some("tags________")) {
+        for tag_folder in children.get(&tags_root.id).into_iter().flatten() {
+            let tag = match tag_folder.title.as_deref().map(str::trim) {
+                Some(tag) if !tag.is_empty() => tag,
+                _ => continue,
+            };
+            for entry in children.get(&tag_folder.id).into_iter().flatten() {
+                if let Some(place_id) = entry.place_id {
+                    tags.entry(place_id).or_default().insert(tag.to_string());
+                }
+            }
+        }
+    }
+
+    let tree = FirefoxTree { children, tags };
+
+    let mut bookmarks = Vec::new();
+    let mut position = 0;
+    for root in tree.children.get(&root_id).into_iter().flatten() {
+        let title = match root.guid.as_deref().and_then(firefox_root_title) {
+            Some(title) => title,
+            None => continue,
+        };
+        if !tree.children.contains_key(&root.id) {
+            continue;
+        }
+
+        push_firefox_node(root, Some(title), None, position, &tree, &mut bookmarks);
+        position += 1;
+    }
+
+    bookmarks
+}
+
+fn push_firefox_node(
+    row: &PlacesBookmark,
+    title_override: Option<&str>,
+    parent_id: Option<&str>,
+    position: i64,
+    tree: &FirefoxTree,
+    bookmarks: &mut Vec<Bookmark>,
+) {
+    let kind = match row.kind {
+        PLACES_TYPE_BOOKMARK if row.url.is_some() => BookmarkKind::Bookmark,
+        PLACES_TYPE_FOLDER => BookmarkKind::Folder,
+        PLACES_TYPE_SEPARATOR => BookmarkKind::Separator,
+        _ => return,
+    };
+
+    let id = new_import_id();
+    let title = match kind {
+        BookmarkKind::Separator => String::new(),
+        _ => title_override
+            .map(|t| t.to_string())
+            .or_else(|| row.title.clone())
+            .unwrap_or_else(|| "Untitled".to_string()),
+    };
+    let (tags, keyword) = if kind == BookmarkKind::Bookmark {
+        (
+            row.place_id
+                .and_then(|place_id| tree.tags.get(&place_id).cloned())
+                .unwrap_or_default(),
+            row.keyword.clone(),
+        )
+    } else {
+        (BTreeSet::new(), None)
+    };
+
+    bookmarks.push(Bookmark {
+        id: id.clone(),
+        url: row.url.clone().unwrap_or_default(),
+        title,
+        favicon: None,
+        created_at: row
+            .date_added
+            .map(|t| firefox_time_to_timestamp(t) * 1000)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+        kind,
+        parent_id: parent_id.map(|s| s.to_string()),
+        position,
+        tags,
+        keyword,
+    });
+
+    if kind == BookmarkKind::Folder {
+        for (i, child) in tree.children.get(&row.id).into_iter().flatten().enumerate() {
+            push_firefox_node(child, None, Some(&id), i as i64, tree, bookmarks);
+        }
+    }
+}
+
 fn firefox_time_to_timestamp(firefox_time: i64) -> i64 {
     // Firefox использует время с 1970-01-01 в микросекундах
     firefox_time / 1000000
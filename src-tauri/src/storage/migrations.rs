@@ -0,0 +1,251 @@
+//! Версия формата данных и миграции хранилища
+//!
+//! В `manifest.json` записывается версия схемы каталога данных. При запуске
+//! выполняются по порядку все шаги из [`MIGRATIONS`] с версией выше записанной;
+//! после каждого успешного шага манифест обновляется, так что прерванное обновление
+//! продолжится со следующего шага. Каталог без манифеста считается версией 0.
+//!
+//! Новый шаг добавляется в конец списка с версией на единицу больше предыдущей.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::atomic::{read_json_blocking, write_json_blocking};
+use super::bookmarks::children_of;
+use super::{ensure_data_dir, history, Bookmark};
+
+const MANIFEST: &str = "manifest.json";
+
+/// Текущая версия формата данных
+pub const STORAGE_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
+    /// Версия приложения, которая последней обновляла каталог
+    #[serde(rename = "appVersion", default)]
+    app_version: String,
+}
+
+struct Migration {
+    /// Версия, до которой шаг обновляет данные
+    version: u32,
+    run: fn(&Path) -> Result<(), String>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    // История из history.json переезжает в SQLite
+    Migration {
+        version: 1,
+        run: history::migrate_legacy_json,
+    },
+    // Закладки получают явные позиции внутри папок
+    Migration {
+        version: 2,
+        run: number_bookmark_positions,
+    },
+];
+
+/// Пронумеровать закладки 0..n внутри каждой папки, сохраняя текущий порядок
+fn number_bookmark_positions(data_dir: &Path) -> Result<(), String> {
+    let path = data_dir.join("bookmarks.json");
+    let mut bookmarks: Vec<Bookmark> = match read_json_blocking(&path)? {
+        Some(bookmarks) => bookmarks,
+        None => return Ok(()),
+    };
+
+    let mut parents: Vec<Option<String>> = Vec::new();
+    for b in &bookmarks {
+        if !parents.contains(&b.parent_id) {
+            parents.push(b.parent_id.clone());
+        }
+    }
+
+    for parent in parents {
+        for (position, i) in children_of(&bookmarks, parent.as_deref()).into_iter().enumerate() {
+            bookmarks[i].position = position as i64;
+        }
+    }
+
+    write_json_blocking(&path, &bookmarks)
+}
+
+fn read_version(data_dir: &Path) -> Result<u32, String> {
+    let manifest: Option<Manifest> = read_json_blocking(&data_dir.join(MANIFEST))?;
+    Ok(manifest.map(|m| m.schema_version).unwrap_or(0))
+}
+
+fn write_version(data_dir: &Path, version: u32) -> Result<(), String> {
+    let manifest = Manifest {
+        schema_version: version,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    write_json_blocking(&data_dir.join(MANIFEST), &manifest)
+}
+
+/// Обновить каталог данных до [`STORAGE_VERSION`], вернуть исходную версию
+pub(super) fn migrate_data_dir(data_dir: &Path) -> Result<u32, String> {
+    let from = read_version(data_dir)?;
+
+    if from > STORAGE_VERSION {
+        return Err(format!(
+            "Data directory {} has storage version {}, but this build supports up to {}",
+            data_dir.display(),
+            from,
+            STORAGE_VERSION
+        ));
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from) {
+        (migration.run)(data_dir)
+            .map_err(|e| format!("Storage migration to version {} failed: {}", migration.version, e))?;
+        write_version(data_dir, migration.version)?;
+    }
+
+    Ok(from)
+}
+
+/// Выполнить миграции хранилища (вызывается при запуске, до первого обращения к данным)
+pub fn migrate_storage() -> Result<u32, String> {
+    let data_dir = ensure_data_dir()?;
+    migrate_data_dir(&data_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::history::open_history_db;
+    use crate::test_support::TempDir;
+
+    /// Скопировать `tests/fixtures/storage/<name>` во временный каталог
+    fn fixture(name: &str) -> TempDir {
+        fn copy_dir(from: &Path, to: &Path) {
+            std::fs::create_dir_all(to).unwrap();
+            for entry in std::fs::read_dir(from).unwrap() {
+                let entry = entry.unwrap();
+                let target = to.join(entry.file_name());
+                if entry.file_type().unwrap().is_dir() {
+                    copy_dir(&entry.path(), &target);
+                } else {
+                    std::fs::copy(entry.path(), target).unwrap();
+                }
+            }
+        }
+
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/storage")
+            .join(name);
+        let dir = TempDir::new(&format!("storage-{}", name));
+        copy_dir(&source, &dir);
+        dir
+    }
+
+    fn read_bookmarks(dir: &Path) -> Vec<Bookmark> {
+        read_json_blocking(&dir.join("bookmarks.json")).unwrap().unwrap()
+    }
+
+    fn positions(bookmarks: &[Bookmark], parent_id: Option<&str>) -> Vec<(String, i64)> {
+        let mut children: Vec<(String, i64)> = bookmarks
+            .iter()
+            .filter(|b| b.parent_id.as_deref() == parent_id)
+            .map(|b| (b.id.clone(), b.position))
+            .collect();
+        children.sort_by_key(|(_, position)| *position);
+        children
+    }
+
+    #[test]
+    fn migrations_are_ordered_and_end_at_current_version() {
+        let versions: Vec<u32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let expected: Vec<u32> = (1..=STORAGE_VERSION).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn upgrades_v0_data_dir() {
+        let dir = fixture("v0");
+
+        assert_eq!(migrate_data_dir(&dir).unwrap(), 0);
+        assert_eq!(read_version(&dir).unwrap(), STORAGE_VERSION);
+
+        // История перенесена в SQLite, старый файл сохранён под другим именем
+        assert!(!dir.join("history.json").exists());
+        assert!(dir.join("history.json.migrated").exists());
+        let conn = open_history_db(&dir).unwrap();
+        let visits: i64 = conn
+            .query_row("SELECT COUNT(*) FROM visits", [], |row| row.get(0))
+            .unwrap();
+        let rust_count: i64 = conn
+            .query_row(
+                "SELECT visit_count FROM urls WHERE url = 'https://www.rust-lang.org/'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(visits, 3);
+        assert_eq!(rust_count, 2);
+
+        // Плоский список закладок пронумерован в порядке файла
+        let bookmarks = read_bookmarks(&dir);
+        assert_eq!(
+            positions(&bookmarks, None),
+            vec![("b1".to_string(), 0), ("b2".to_string(), 1), ("b3".to_string(), 2)]
+        );
+
+        // Остальные файлы не тронуты
+        let settings: serde_json::Value = read_json_blocking(&dir.join("settings.json")).unwrap().unwrap();
+        assert_eq!(settings["searchEngine"], "duckduckgo");
+    }
+
+    #[test]
+    fn upgrades_v1_data_dir() {
+        let dir = fixture("v1");
+
+        assert_eq!(migrate_data_dir(&dir).unwrap(), 1);
+        assert_eq!(read_version(&dir).unwrap(), STORAGE_VERSION);
+
+        let bookmarks = read_bookmarks(&dir);
+        assert_eq!(
+            positions(&bookmarks, None),
+            vec![("dev".to_string(), 0), ("news".to_string(), 1)]
+        );
+        assert_eq!(
+            positions(&bookmarks, Some("dev")),
+            vec![("gh".to_string(), 0), ("sep".to_string(), 1), ("docs".to_string(), 2)]
+        );
+        // Уже заданный порядок внутри папки сохраняется
+        assert_eq!(
+            positions(&bookmarks, Some("news")),
+            vec![("hn".to_string(), 0), ("lobsters".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn migration_is_idempotent() {
+        let dir = fixture("v0");
+        migrate_data_dir(&dir).unwrap();
+        let before = std::fs::read_to_string(dir.join("bookmarks.json")).unwrap();
+
+        assert_eq!(migrate_data_dir(&dir).unwrap(), STORAGE_VERSION);
+        assert_eq!(std::fs::read_to_string(dir.join("bookmarks.json")).unwrap(), before);
+    }
+
+    #[test]
+    fn fresh_data_dir_gets_manifest() {
+        let dir = TempDir::new("storage-empty");
+
+        assert_eq!(migrate_data_dir(&dir).unwrap(), 0);
+        assert_eq!(read_version(&dir).unwrap(), STORAGE_VERSION);
+        assert!(!dir.join("bookmarks.json").exists());
+    }
+
+    #[test]
+    fn refuses_newer_data_dir() {
+        let dir = TempDir::new("storage-newer");
+        write_version(&dir, STORAGE_VERSION + 1).unwrap();
+
+        assert!(migrate_data_dir(&dir).is_err());
+        assert_eq!(read_version(&dir).unwrap(), STORAGE_VERSION + 1);
+    }
+}
@@ -1,27 +1,72 @@
+mod atomic;
+mod autocomplete;
 mod bookmarks;
 mod history;
 mod import;
+mod migrations;
+mod netscape;
+mod profiles;
+mod search;
 mod session;
 mod settings;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 
 // Re-exports
-pub use bookmarks::{get_bookmarks, set_bookmarks};
+pub use atomic::{read_json, remove_json, write_json};
+pub use autocomplete::{autocomplete, OpenTab, QuickSite, Suggestion};
+pub use bookmarks::{
+    create_bookmark_folder, get_bookmarks, get_bookmarks_by_tag, move_bookmark, reorder_bookmarks,
+    resolve_keyword, set_bookmarks,
+};
 pub use history::{add_history, clear_history, get_history, set_history};
 pub use import::{detect_browsers, import_from_browser, DetectedBrowser, ImportResult};
+pub use migrations::{migrate_storage, STORAGE_VERSION};
+pub use netscape::{is_netscape_html, parse_netscape_html, to_netscape_html};
+pub use profiles::{
+    active_profile_id, create_profile, delete_profile, get_profile, list_profiles,
+    rename_profile, select_profile, webview_data_dir, Profile, ProfileList,
+};
+pub use search::{
+    search_bookmarks, search_history, BookmarkSearchHit, HistorySearchHit, SearchResults,
+};
 pub use session::{clear_session, restore_session, save_session};
-pub use settings::{get_settings, set_settings};
+pub use settings::{get_settings, set_settings, DownloadRule, Settings, SettingsChanged};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bookmark {
     pub id: String,
+    /// Пустой у папок и разделителей
+    #[serde(default)]
     pub url: String,
     pub title: String,
     pub favicon: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: i64,
+    #[serde(rename = "type", default)]
+    pub kind: BookmarkKind,
+    /// ID родительской папки (None — верхний уровень)
+    #[serde(rename = "parentId", default)]
+    pub parent_id: Option<String>,
+    /// Порядок внутри родительской папки
+    #[serde(default)]
+    pub position: i64,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// Ключевое слово для адресной строки: `gh rust` подставляет `rust` вместо `%s` в URL
+    #[serde(default)]
+    pub keyword: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkKind {
+    #[default]
+    Bookmark,
+    Folder,
+    Separator,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,12 +77,44 @@ pub struct HistoryEntry {
     pub favicon: Option<String>,
     #[serde(rename = "visitedAt")]
     pub visited_at: i64,
+    #[serde(rename = "visitCount", default = "default_visit_count")]
+    pub visit_count: i64,
+    #[serde(default)]
+    pub transition: Transition,
+}
+
+/// Как пользователь попал на страницу: ввёл адрес сам или перешёл по ссылке
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transition {
+    #[default]
+    Link,
+    Typed,
+}
+
+impl Transition {
+    pub fn as_i64(self) -> i64 {
+        match self {
+            Transition::Link => 0,
+            Transition::Typed => 1,
+        }
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        match value {
+            1 => Transition::Typed,
+            _ => Transition::Link,
+        }
+    }
+}
+
+fn default_visit_count() -> i64 {
+    1
 }
 
+/// Каталог данных активного профиля
 pub fn get_data_dir() -> Result<PathBuf, String> {
-    dirs::data_dir()
-        .ok_or_else(|| "Could not find data directory".to_string())
-        .map(|p| p.join("axion-browser"))
+    profiles::active_profile_dir()
 }
 
 pub fn ensure_data_dir() -> Result<PathBuf, String> {
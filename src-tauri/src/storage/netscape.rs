@@ -0,0 +1,509 @@
+//! Формат NETSCAPE-Bookmark-file-1 (HTML-экспорт закладок всех браузеров)
+//!
+//! Поддерживаются папки (`<H3>` + `<DL>`), разделители (`<HR>`), а также атрибуты
+//! `ADD_DATE`, `ICON`, `TAGS` и `SHORTCUTURL`.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::bookmarks::children_of;
+use super::{Bookmark, BookmarkKind};
+
+const HEADER: &str = "<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+";
+
+const DOCTYPE: &str = "NETSCAPE-Bookmark-file-1";
+
+/// Netscape HTML ли это: файл начинается с `<!DOCTYPE NETSCAPE-Bookmark-file-1>`
+/// (регистр и пробелы браузеры пишут по-разному)
+pub fn is_netscape_html(content: &str) -> bool {
+    let content = content.trim_start_matches('\u{feff}').trim_start();
+    let is_doctype = content.get(..9).is_some_and(|tag| tag.eq_ignore_ascii_case("<!doctype"));
+    if !is_doctype {
+        return false;
+    }
+    let name = content[9..].trim_start();
+    name.get(..DOCTYPE.len()).is_some_and(|name| name.eq_ignore_ascii_case(DOCTYPE))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let semicolon = match rest.find(';') {
+            Some(i) if i <= 10 => i,
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let entity = &rest[1..semicolon];
+        let ch = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse::<u32>().ok().and_then(char::from_u32)
+                } else {
+                    None
+                }
+            }
+        };
+
+        match ch {
+            Some(ch) => {
+                decoded.push(ch);
+                rest = &rest[semicolon + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Разобранный открывающий или закрывающий тег
+struct Tag {
+    /// Имя в нижнем регистре, для закрывающих тегов с `/` в начале
+    name: String,
+    attrs: HashMap<String, String>,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(|s| s.as_str())
+    }
+}
+
+/// Позиция `>`, закрывающей тег, с учётом кавычек в атрибутах
+fn find_tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, ch) in input.char_indices() {
+        match (quote, ch) {
+            (None, '"') | (None, '\'') => quote = Some(ch),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Разбор содержимого между `<` и `>`
+fn parse_tag(inner: &str) -> Tag {
+    let inner = inner.trim().trim_end_matches('/');
+    let name_end = inner.find(char::is_whitespace).unwrap_or(inner.len());
+    let name = inner[..name_end].to_ascii_lowercase();
+
+    let mut attrs = HashMap::new();
+    let mut rest = inner[name_end..].trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(q @ ('"' | '\'')) => {
+                    let body = &after_eq[1..];
+                    let end = body.find(q).unwrap_or(body.len());
+                    rest = body.get(end + 1..).unwrap_or("");
+                    &body[..end]
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    rest = &after_eq[end..];
+                    &after_eq[..end]
+                }
+            }
+        } else {
+            ""
+        };
+
+        if !key.is_empty() {
+            attrs.insert(key, decode_entities(value));
+        }
+        rest = rest.trim_start();
+    }
+
+    Tag { name, attrs }
+}
+
+/// Текст до закрывающего тега `close` (например `</a`) и остаток после него
+fn read_until_close<'a>(input: &'a str, close: &str) -> (&'a str, &'a str) {
+    let bytes = input.as_bytes();
+    let close = close.as_bytes();
+
+    let mut i = 0;
+    while let Some(offset) = input[i..].find('<') {
+        let at = i + offset;
+        if bytes.len() >= at + close.len() && bytes[at..at + close.len()].eq_ignore_ascii_case(close) {
+            let after = match input[at..].find('>') {
+                Some(end) => &input[at + end + 1..],
+                None => "",
+            };
+            return (&input[..at], after);
+        }
+        i = at + 1;
+    }
+
+    (input, "")
+}
+
+/// ADD_DATE хранится в секундах, у нас — миллисекунды
+fn parse_add_date(tag: &Tag) -> i64 {
+    tag.attr("add_date")
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .map(|secs| secs * 1000)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis())
+}
+
+/// Разобрать Netscape HTML в плоский список закладок с parentId/position
+pub fn parse_netscape_html(html: &str) -> Vec<Bookmark> {
+    let mut bookmarks = Vec::new();
+    // Папка, открытая каждым <DL> (None — корневой или "лишний" список)
+    let mut stack: Vec<Option<String>> = Vec::new();
+    // Папка из последнего <H3>, её содержимое начнётся со следующего <DL>
+    let mut pending_folder: Option<String> = None;
+    let mut positions: HashMap<Option<String>, i64> = HashMap::new();
+
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            rest = match rest.find("-->") {
+                Some(end) => &rest[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+
+        let end = match find_tag_end(rest) {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = parse_tag(&rest[1..end]);
+        rest = &rest[end + 1..];
+
+        let parent_id = stack.iter().rev().find_map(|f| f.clone());
+
+        match tag.name.as_str() {
+            "dl" => stack.push(pending_folder.take()),
+            "/dl" => {
+                stack.pop();
+            }
+            "h3" => {
+                let (text, after) = read_until_close(rest, "</h3");
+                rest = after;
+
+                let position = positions.entry(parent_id.clone()).or_insert(0);
+                let id = uuid::Uuid::new_v4().to_string();
+                bookmarks.push(Bookmark {
+                    id: id.clone(),
+                    url: String::new(),
+                    title: decode_entities(text.trim()),
+                    favicon: None,
+                    created_at: parse_add_date(&tag),
+                    kind: BookmarkKind::Folder,
+                    parent_id,
+                    position: *position,
+                    tags: BTreeSet::new(),
+                    keyword: None,
+                });
+                *position += 1;
+                pending_folder = Some(id);
+            }
+            "a" => {
+                let (text, after) = read_until_close(rest, "</a");
+                rest = after;
+                pending_folder = None;
+
+                let url = match tag.attr("href") {
+                    Some(href) if !href.trim().is_empty() => href.trim().to_string(),
+                    _ => continue,
+                };
+                let tags = tag
+                    .attr("tags")
+                    .map(|t| {
+                        t.split(',')
+                            .map(|s| s.trim())
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let keyword = tag
+                    .attr("shortcuturl")
+                    .map(|k| k.trim())
+                    .filter(|k| !k.is_empty())
+                    .map(|k| k.to_string());
+                let favicon = tag
+                    .attr("icon")
+                    .filter(|i| i.starts_with("data:"))
+                    .map(|i| i.to_string());
+
+                let position = positions.entry(parent_id.clone()).or_insert(0);
+                bookmarks.push(Bookmark {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    url,
+                    title: decode_entities(text.trim()),
+                    favicon,
+                    created_at: parse_add_date(&tag),
+                    kind: BookmarkKind::Bookmark,
+                    parent_id,
+                    position: *position,
+                    tags,
+                    keyword,
+                });
+                *position += 1;
+            }
+            "hr" => {
+                pending_folder = None;
+
+                let position = positions.entry(parent_id.clone()).or_insert(0);
+                bookmarks.push(Bookmark {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    url: String::new(),
+                    title: String::new(),
+                    favicon: None,
+                    created_at: chrono::Utc::now().timestamp_millis(),
+                    kind: BookmarkKind::Separator,
+                    parent_id,
+                    position: *position,
+                    tags: BTreeSet::new(),
+                    keyword: None,
+                });
+                *position += 1;
+            }
+            "title" | "h1" => {
+                // Заголовок документа в закладки не попадает
+                let (_, after) = read_until_close(rest, &format!("</{}", tag.name));
+                rest = after;
+            }
+            _ => {}
+        }
+    }
+
+    bookmarks
+}
+
+fn write_folder(bookmarks: &[Bookmark], parent_id: Option<&str>, depth: usize, out: &mut String) {
+    let indent = "    ".repeat(depth);
+    out.push_str(&indent);
+    out.push_str("<DL><p>\n");
+
+    for i in children_of(bookmarks, parent_id) {
+        let b = &bookmarks[i];
+        let add_date = b.created_at / 1000;
+        out.push_str(&indent);
+        out.push_str("    ");
+
+        match b.kind {
+            BookmarkKind::Folder => {
+                out.push_str(&format!(
+                    "<DT><H3 ADD_DATE=\"{}\">{}</H3>\n",
+                    add_date,
+                    escape_html(&b.title)
+                ));
+                write_folder(bookmarks, Some(&b.id), depth + 1, out);
+            }
+            BookmarkKind::Separator => out.push_str("<HR>\n"),
+            BookmarkKind::Bookmark => {
+                out.push_str(&format!(
+                    "<DT><A HREF=\"{}\" ADD_DATE=\"{}\"",
+                    escape_html(&b.url),
+                    add_date
+                ));
+                if let Some(icon) = b.favicon.as_deref().filter(|i| i.starts_with("data:")) {
+                    out.push_str(&format!(" ICON=\"{}\"", escape_html(icon)));
+                }
+                if !b.tags.is_empty() {
+                    let tags: Vec<&str> = b.tags.iter().map(|t| t.as_str()).collect();
+                    out.push_str(&format!(" TAGS=\"{}\"", escape_html(&tags.join(","))));
+                }
+                if let Some(keyword) = b.keyword.as_deref() {
+                    out.push_str(&format!(" SHORTCUTURL=\"{}\"", escape_html(keyword)));
+                }
+                out.push_str(&format!(">{}</A>\n", escape_html(&b.title)));
+            }
+        }
+    }
+
+    out.push_str(&indent);
+    out.push_str("</DL><p>\n");
+}
+
+/// Сохранить закладки в формате Netscape HTML
+pub fn to_netscape_html(bookmarks: &[Bookmark]) -> String {
+    // Закладки из несуществующих папок выгружаем на верхний уровень, чтобы не потерять
+    let mut bookmarks = bookmarks.to_vec();
+    let folder_ids: BTreeSet<String> = bookmarks
+        .iter()
+        .filter(|b| b.kind == BookmarkKind::Folder)
+        .map(|b| b.id.clone())
+        .collect();
+    for b in bookmarks.iter_mut() {
+        if b.parent_id.as_ref().is_some_and(|p| !folder_ids.contains(p)) {
+            b.parent_id = None;
+        }
+    }
+
+    let mut out = String::from(HEADER);
+    write_folder(&bookmarks, None, 0, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn fixture(name: &str) -> String {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/bookmarks")
+            .join(name);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    fn find<'a>(bookmarks: &'a [Bookmark], title: &str) -> &'a Bookmark {
+        bookmarks.iter().find(|b| b.title == title).unwrap()
+    }
+
+    /// Путь из названий папок до закладки
+    fn path_of(bookmarks: &[Bookmark], bookmark: &Bookmark) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut parent = bookmark.parent_id.clone();
+        while let Some(id) = parent {
+            let folder = bookmarks.iter().find(|b| b.id == id).unwrap();
+            path.insert(0, folder.title.clone());
+            parent = folder.parent_id.clone();
+        }
+        path
+    }
+
+    #[test]
+    fn detects_doctype() {
+        assert!(is_netscape_html(&fixture("chrome.html")));
+        assert!(is_netscape_html("\u{feff}  <!doctype  netscape-bookmark-file-1>\n<DL>"));
+        assert!(!is_netscape_html("<html><body>not bookmarks</body></html>"));
+        assert!(!is_netscape_html(r#"[{"id":"b1","url":"https://a","title":"<b>"}]"#));
+    }
+
+    #[test]
+    fn parses_chrome_export() {
+        let bookmarks = parse_netscape_html(&fixture("chrome.html"));
+        assert_eq!(bookmarks.len(), 6);
+
+        let bar = find(&bookmarks, "Bookmarks bar");
+        assert_eq!(bar.kind, BookmarkKind::Folder);
+        assert_eq!(bar.created_at, 1_700_000_000_000);
+        assert_eq!(bar.parent_id, None);
+
+        let rust = find(&bookmarks, "Rust Programming Language");
+        assert_eq!(rust.parent_id.as_deref(), Some(bar.id.as_str()));
+        assert_eq!(rust.favicon.as_deref(), Some("data:image/png;base64,iVBORw0KGgo="));
+
+        // Вложенная папка и экранированные сущности в названиях и адресах
+        let book = find(&bookmarks, "The Book <2nd>");
+        assert_eq!(book.url, "https://doc.rust-lang.org/book/?a=1&b=2");
+        assert_eq!(path_of(&bookmarks, book), vec!["Bookmarks bar", "Docs & Guides"]);
+
+        // Иконка-ссылка не сохраняется, только data:
+        let quoted = find(&bookmarks, "It's \"quoted\"");
+        assert_eq!(quoted.favicon, None);
+        assert_eq!(path_of(&bookmarks, quoted), vec!["Other bookmarks"]);
+        assert_eq!(find(&bookmarks, "Other bookmarks").position, 1);
+    }
+
+    #[test]
+    fn parses_firefox_export() {
+        let bookmarks = parse_netscape_html(&fixture("firefox.html"));
+
+        let mozilla = find(&bookmarks, "Mozilla");
+        assert_eq!(mozilla.keyword.as_deref(), Some("moz"));
+        assert_eq!(
+            mozilla.tags.iter().map(|t| t.as_str()).collect::<Vec<_>>(),
+            vec!["browser", "mozilla"]
+        );
+        assert_eq!(mozilla.favicon.as_deref(), Some("data:image/png;base64,AAAA"));
+        assert_eq!(mozilla.position, 0);
+
+        // <DD> с описанием не становится закладкой, <HR> — разделитель
+        assert!(!bookmarks.iter().any(|b| b.title.contains("Description")));
+        let separator = bookmarks.iter().find(|b| b.kind == BookmarkKind::Separator).unwrap();
+        assert_eq!((separator.parent_id.as_deref(), separator.position), (None, 1));
+
+        let search = find(&bookmarks, "Search %s");
+        assert_eq!(search.keyword.as_deref(), Some("s"));
+        assert_eq!(path_of(&bookmarks, search), vec!["Bookmarks Toolbar"]);
+
+        let empty = find(&bookmarks, "Empty folder");
+        assert_eq!(empty.kind, BookmarkKind::Folder);
+        assert!(!bookmarks.iter().any(|b| b.parent_id.as_deref() == Some(empty.id.as_str())));
+        assert_eq!(path_of(&bookmarks, find(&bookmarks, "Recent Tags")), vec!["Other Bookmarks"]);
+    }
+
+    #[test]
+    fn round_trips_through_html() {
+        for name in ["chrome.html", "firefox.html"] {
+            let parsed = parse_netscape_html(&fixture(name));
+            let reparsed = parse_netscape_html(&to_netscape_html(&parsed));
+            assert!(is_netscape_html(&to_netscape_html(&parsed)));
+            assert_eq!(parsed.len(), reparsed.len(), "{}", name);
+
+            for (before, after) in parsed.iter().zip(&reparsed) {
+                assert_eq!(before.title, after.title, "{}", name);
+                assert_eq!(before.url, after.url);
+                assert_eq!(before.kind, after.kind);
+                // У разделителей нет ADD_DATE — время ставится при разборе
+                if before.kind != BookmarkKind::Separator {
+                    assert_eq!(before.created_at, after.created_at);
+                }
+                assert_eq!(before.position, after.position);
+                assert_eq!(before.favicon, after.favicon);
+                assert_eq!(before.tags, after.tags);
+                assert_eq!(before.keyword, after.keyword);
+                assert_eq!(path_of(&parsed, before), path_of(&reparsed, after));
+            }
+        }
+    }
+}
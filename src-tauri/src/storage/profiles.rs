@@ -0,0 +1,336 @@
+//! Профили пользователя
+//!
+//! У каждого профиля свой каталог данных (история, закладки, настройки, сессия,
+//! загрузки) и свой каталог данных WebView (cookies, localStorage, кэш).
+//! Профиль `default` живёт прямо в `axion-browser/` — там, где данные лежали
+//! до появления профилей; остальные — в `axion-browser/profiles/<id>/`.
+//!
+//! Профиль выбирается один раз при запуске (`--profile <имя>` или последний
+//! использованный); переключение перезапускает приложение.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::atomic::{read_json, read_json_blocking, write_json, write_json_blocking};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+const PROFILES_FILE: &str = "profiles.json";
+const MAX_NAME_LEN: usize = 64;
+
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+}
+
+/// Список профилей для фронтенда
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    /// ID профиля, с которым запущено приложение
+    pub active: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    profiles: Vec<Profile>,
+    #[serde(rename = "lastUsed", default)]
+    last_used: Option<String>,
+}
+
+impl Registry {
+    /// Профиль по умолчанию есть всегда
+    fn normalized(mut self) -> Self {
+        if !self.profiles.iter().any(|p| p.id == DEFAULT_PROFILE) {
+            self.profiles.insert(
+                0,
+                Profile {
+                    id: DEFAULT_PROFILE.to_string(),
+                    name: "Default".to_string(),
+                    created_at: chrono::Utc::now().timestamp_millis(),
+                },
+            );
+        }
+        self
+    }
+
+    fn find(&self, id: &str) -> Result<usize, String> {
+        self.profiles
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| format!("Profile {} not found", id))
+    }
+
+    /// Профиль по ID или имени (без учёта регистра)
+    fn lookup(&self, requested: &str) -> Result<usize, String> {
+        self.profiles
+            .iter()
+            .position(|p| p.id == requested)
+            .or_else(|| {
+                self.profiles
+                    .iter()
+                    .position(|p| p.name.to_lowercase() == requested.to_lowercase())
+            })
+            .ok_or_else(|| format!("Profile {} not found", requested))
+    }
+
+    /// Проверить имя: не пустое, не слишком длинное и не занятое другим профилем
+    fn check_name(&self, name: &str, except_id: Option<&str>) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name cannot be empty".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(format!("Profile name is longer than {} characters", MAX_NAME_LEN));
+        }
+        let taken = self
+            .profiles
+            .iter()
+            .any(|p| Some(p.id.as_str()) != except_id && p.name.to_lowercase() == name.to_lowercase());
+        if taken {
+            return Err(format!("Profile \"{}\" already exists", name));
+        }
+        Ok(name.to_string())
+    }
+
+    fn add(&mut self, name: &str) -> Result<Profile, String> {
+        let profile = Profile {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            name: self.check_name(name, None)?,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    fn rename(&mut self, id: &str, name: &str) -> Result<Profile, String> {
+        let index = self.find(id)?;
+        self.profiles[index].name = self.check_name(name, Some(id))?;
+        Ok(self.profiles[index].clone())
+    }
+
+    /// Убрать профиль из списка. Профиль по умолчанию и профиль `active`,
+    /// с которым запущено приложение, удалить нельзя
+    fn remove(&mut self, id: &str, active: &str) -> Result<Profile, String> {
+        if id == DEFAULT_PROFILE {
+            return Err("The default profile cannot be deleted".to_string());
+        }
+        if id == active {
+            return Err("The active profile cannot be deleted".to_string());
+        }
+        let index = self.find(id)?;
+        if self.last_used.as_deref() == Some(id) {
+            self.last_used = None;
+        }
+        Ok(self.profiles.remove(index))
+    }
+}
+
+/// Общий каталог приложения (над каталогами профилей)
+fn root_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .ok_or_else(|| "Could not find data directory".to_string())
+        .map(|p| p.join("axion-browser"))
+}
+
+fn profile_dir(root: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE {
+        root.to_path_buf()
+    } else {
+        root.join("profiles").join(id)
+    }
+}
+
+/// ID профиля, с которым запущено приложение
+pub fn active_profile_id() -> &'static str {
+    ACTIVE_PROFILE
+        .get()
+        .map(|id| id.as_str())
+        .unwrap_or(DEFAULT_PROFILE)
+}
+
+/// Каталог данных активного профиля
+pub(super) fn active_profile_dir() -> Result<PathBuf, String> {
+    Ok(profile_dir(&root_dir()?, active_profile_id()))
+}
+
+/// Каталог данных WebView активного профиля.
+/// `None` для профиля по умолчанию — он использует каталог WebView как раньше,
+/// чтобы не потерять cookies и сессии сайтов.
+pub fn webview_data_dir() -> Result<Option<PathBuf>, String> {
+    if active_profile_id() == DEFAULT_PROFILE {
+        return Ok(None);
+    }
+    Ok(Some(active_profile_dir()?.join("webview")))
+}
+
+/// Выбрать профиль при запуске: по аргументу `--profile` (ID или имя существующего
+/// профиля — новые создаются только через [`create_profile`]), иначе последний
+/// использованный.
+pub fn select_profile(requested: Option<&str>) -> Result<Profile, String> {
+    let root = root_dir()?;
+    std::fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+    let path = root.join(PROFILES_FILE);
+
+    let mut registry = read_json_blocking::<Registry>(&path)?
+        .unwrap_or_default()
+        .normalized();
+
+    let index = match requested.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(requested) => registry.lookup(requested)?,
+        None => registry
+            .last_used
+            .as_deref()
+            .and_then(|id| registry.find(id).ok())
+            .unwrap_or(0),
+    };
+
+    let profile = registry.profiles[index].clone();
+    registry.last_used = Some(profile.id.clone());
+    write_json_blocking(&path, &registry)?;
+
+    std::fs::create_dir_all(profile_dir(&root, &profile.id)).map_err(|e| e.to_string())?;
+    ACTIVE_PROFILE
+        .set(profile.id.clone())
+        .map_err(|_| "Profile is already selected".to_string())?;
+
+    Ok(profile)
+}
+
+async fn load_registry() -> Result<(PathBuf, Registry), String> {
+    let root = root_dir()?;
+    tokio::fs::create_dir_all(&root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let path = root.join(PROFILES_FILE);
+    let registry = read_json::<Registry>(&path)
+        .await?
+        .unwrap_or_default()
+        .normalized();
+    Ok((path, registry))
+}
+
+pub async fn list_profiles() -> Result<ProfileList, String> {
+    let (_, registry) = load_registry().await?;
+
+    Ok(ProfileList {
+        profiles: registry.profiles,
+        active: active_profile_id().to_string(),
+    })
+}
+
+pub async fn create_profile(name: String) -> Result<Profile, String> {
+    let (path, mut registry) = load_registry().await?;
+    let profile = registry.add(&name)?;
+
+    tokio::fs::create_dir_all(profile_dir(&root_dir()?, &profile.id))
+        .await
+        .map_err(|e| e.to_string())?;
+    write_json(&path, &registry).await?;
+
+    Ok(profile)
+}
+
+pub async fn rename_profile(id: String, name: String) -> Result<Profile, String> {
+    let (path, mut registry) = load_registry().await?;
+    let profile = registry.rename(&id, &name)?;
+    write_json(&path, &registry).await?;
+
+    Ok(profile)
+}
+
+/// Удалить профиль вместе со всеми его данными
+pub async fn delete_profile(id: String) -> Result<(), String> {
+    let (path, mut registry) = load_registry().await?;
+    registry.remove(&id, active_profile_id())?;
+    write_json(&path, &registry).await?;
+
+    let dir = profile_dir(&root_dir()?, &id);
+    if dir.exists() {
+        tokio::fs::remove_dir_all(dir)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_profile(id: String) -> Result<Profile, String> {
+    let (_, registry) = load_registry().await?;
+    let index = registry.find(&id)?;
+
+    Ok(registry.profiles[index].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_profiles_with_unique_names() {
+        let mut registry = Registry::default().normalized();
+        assert_eq!(registry.profiles[0].id, DEFAULT_PROFILE);
+
+        let work = registry.add("  Work ").unwrap();
+        assert_eq!(work.name, "Work");
+        assert_eq!(work.id.len(), 8);
+        assert_eq!(registry.profiles.len(), 2);
+
+        assert!(registry.add("work").is_err());
+        assert!(registry.add("default").is_err());
+        assert!(registry.add("   ").is_err());
+        assert!(registry.add(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+        assert_eq!(registry.profiles.len(), 2);
+    }
+
+    #[test]
+    fn renames_profiles() {
+        let mut registry = Registry::default().normalized();
+        let work = registry.add("Work").unwrap();
+        registry.add("Home").unwrap();
+
+        assert_eq!(registry.rename(&work.id, "Office").unwrap().name, "Office");
+        // Своё имя можно оставить, поменяв регистр, чужое занять нельзя
+        assert_eq!(registry.rename(&work.id, "OFFICE").unwrap().name, "OFFICE");
+        assert!(registry.rename(&work.id, "home").is_err());
+        assert!(registry.rename(&work.id, "").is_err());
+        assert!(registry.rename("missing", "Other").is_err());
+        assert_eq!(registry.profiles[registry.find(&work.id).unwrap()].name, "OFFICE");
+    }
+
+    #[test]
+    fn keeps_default_and_active_profiles() {
+        let mut registry = Registry::default().normalized();
+        let work = registry.add("Work").unwrap();
+        let home = registry.add("Home").unwrap();
+        registry.last_used = Some(home.id.clone());
+
+        assert!(registry.remove(DEFAULT_PROFILE, &work.id).is_err());
+        assert!(registry.remove(&work.id, &work.id).is_err());
+        assert_eq!(registry.profiles.len(), 3);
+
+        assert_eq!(registry.remove(&home.id, DEFAULT_PROFILE).unwrap().id, home.id);
+        assert_eq!(registry.last_used, None);
+        assert!(registry.remove(&home.id, DEFAULT_PROFILE).is_err());
+        assert_eq!(registry.profiles.len(), 2);
+    }
+
+    #[test]
+    fn looks_up_existing_profiles_only() {
+        let mut registry = Registry::default().normalized();
+        let work = registry.add("Work").unwrap();
+
+        assert_eq!(registry.lookup(&work.id), Ok(1));
+        assert_eq!(registry.lookup("WORK"), Ok(1));
+        assert_eq!(registry.lookup(DEFAULT_PROFILE), Ok(0));
+        assert!(registry.lookup("Personal").is_err());
+        assert_eq!(registry.profiles.len(), 2);
+    }
+}
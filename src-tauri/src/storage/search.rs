@@ -0,0 +1,471 @@
+//! Полнотекстовый поиск по истории и закладкам (SQLite FTS5)
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::history::with_history_db;
+use super::{get_bookmarks, Bookmark, BookmarkKind, HistoryEntry, Transition};
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 500;
+
+// Маркеры подсветки, которые highlight() вставляет вокруг совпадений.
+// Управляющие символы не встречаются в URL и заголовках, поэтому их легко вырезать.
+const MARK_START: char = '\u{2}';
+const MARK_END: char = '\u{3}';
+
+/// Индекс закладок живёт в той же базе, что и история
+const BOOKMARKS_FTS_SCHEMA: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS bookmarks_fts USING fts5(
+        id UNINDEXED, url, title,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+";
+
+/// Диапазон совпадения в строке (в UTF-16 единицах, как индексы строк в JS)
+#[derive(Debug, Clone, Serialize)]
+pub struct HighlightRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySearchHit {
+    #[serde(flatten)]
+    pub entry: HistoryEntry,
+    pub rank: f64,
+    #[serde(rename = "titleHighlights")]
+    pub title_highlights: Vec<HighlightRange>,
+    #[serde(rename = "urlHighlights")]
+    pub url_highlights: Vec<HighlightRange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BookmarkSearchHit {
+    #[serde(flatten)]
+    pub bookmark: Bookmark,
+    pub rank: f64,
+    #[serde(rename = "titleHighlights")]
+    pub title_highlights: Vec<HighlightRange>,
+    #[serde(rename = "urlHighlights")]
+    pub url_highlights: Vec<HighlightRange>,
+}
+
+/// Страница результатов поиска
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResults<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+}
+
+impl<T> SearchResults<T> {
+    fn empty() -> Self {
+        Self {
+            items: Vec::new(),
+            total: 0,
+        }
+    }
+}
+
+/// Превратить пользовательский ввод в безопасный запрос FTS5.
+/// Каждое слово берётся в кавычки (чтобы `-`, `:` и `"` не ломали синтаксис)
+/// и ищется как префикс.
+pub(super) fn build_fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Убрать маркеры подсветки и вернуть чистый текст с диапазонами совпадений
+fn split_highlight(marked: &str) -> (String, Vec<HighlightRange>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut ranges = Vec::new();
+    let mut offset = 0usize;
+    let mut start = None;
+
+    for ch in marked.chars() {
+        match ch {
+            MARK_START => start = Some(offset),
+            MARK_END => {
+                if let Some(s) = start.take() {
+                    if offset > s {
+                        ranges.push(HighlightRange { start: s, end: offset });
+                    }
+                }
+            }
+            _ => {
+                text.push(ch);
+                offset += ch.len_utf16();
+            }
+        }
+    }
+
+    (text, ranges)
+}
+
+fn clamp_limit(limit: Option<u32>) -> i64 {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as i64
+}
+
+/// Поиск по истории: одна запись на адрес, у которого есть посещение в интервале [from, to]
+pub async fn search_history(
+    query: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<SearchResults<HistorySearchHit>, String> {
+    let fts_query = match build_fts_query(&query) {
+        Some(q) => q,
+        None => return Ok(SearchResults::empty()),
+    };
+    let limit = clamp_limit(limit);
+    let offset = offset.unwrap_or(0) as i64;
+    let from = from.unwrap_or(i64::MIN);
+    let to = to.unwrap_or(i64::MAX);
+
+    with_history_db(move |conn| find_history(conn, &fts_query, limit, offset, from, to)).await
+}
+
+/// Страница поиска по истории для готового запроса FTS5
+fn find_history(
+    conn: &Connection,
+    fts_query: &str,
+    limit: i64,
+    offset: i64,
+    from: i64,
+    to: i64,
+) -> Result<SearchResults<HistorySearchHit>, String> {
+    let total: i64 = conn
+        .query_row(
+            "
+        SELECT COUNT(*)
+        FROM urls_fts
+        JOIN urls u ON u.id = urls_fts.rowid
+        WHERE urls_fts MATCH ?1
+          AND EXISTS (SELECT 1 FROM visits v WHERE v.url_id = u.id AND v.visit_time BETWEEN ?2 AND ?3)
+    ",
+            params![fts_query, from, to],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "
+        SELECT
+            (SELECT v.id FROM visits v
+             WHERE v.url_id = u.id AND v.visit_time BETWEEN ?2 AND ?3
+             ORDER BY v.visit_time DESC LIMIT 1),
+            highlight(urls_fts, 0, ?6, ?7),
+            highlight(urls_fts, 1, ?6, ?7),
+            u.favicon,
+            (SELECT MAX(v.visit_time) FROM visits v
+             WHERE v.url_id = u.id AND v.visit_time BETWEEN ?2 AND ?3),
+            u.visit_count,
+            bm25(urls_fts, 1.0, 2.0) AS rank
+        FROM urls_fts
+        JOIN urls u ON u.id = urls_fts.rowid
+        WHERE urls_fts MATCH ?1
+          AND EXISTS (SELECT 1 FROM visits v WHERE v.url_id = u.id AND v.visit_time BETWEEN ?2 AND ?3)
+        ORDER BY rank, u.last_visit_time DESC
+        LIMIT ?4 OFFSET ?5
+    ",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                fts_query,
+                from,
+                to,
+                limit,
+                offset,
+                MARK_START.to_string(),
+                MARK_END.to_string()
+            ],
+            |row| {
+                let (url, url_highlights) = split_highlight(&row.get::<_, String>(1)?);
+                let (title, title_highlights) = split_highlight(&row.get::<_, String>(2)?);
+
+                Ok(HistorySearchHit {
+                    entry: HistoryEntry {
+                        id: row.get(0)?,
+                        url,
+                        title,
+                        favicon: row.get(3)?,
+                        visited_at: row.get(4)?,
+                        visit_count: row.get(5)?,
+                        transition: Transition::Link,
+                    },
+                    rank: row.get(6)?,
+                    title_highlights,
+                    url_highlights,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| e.to_string())?);
+    }
+
+    Ok(SearchResults { items, total })
+}
+
+fn write_bookmarks_index(conn: &mut Connection, bookmarks: &[Bookmark]) -> Result<(), String> {
+    conn.execute_batch(BOOKMARKS_FTS_SCHEMA)
+        .map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM bookmarks_fts", [])
+        .map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare("INSERT INTO bookmarks_fts (id, url, title) VALUES (?1, ?2, ?3)")
+            .map_err(|e| e.to_string())?;
+        // Папки и разделители не ищем
+        for bookmark in bookmarks.iter().filter(|b| b.kind == BookmarkKind::Bookmark) {
+            stmt.execute(params![bookmark.id, bookmark.url, bookmark.title])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Перестроить индекс закладок (вызывается при каждом сохранении закладок)
+pub async fn reindex_bookmarks(bookmarks: Vec<Bookmark>) -> Result<(), String> {
+    with_history_db(move |conn| write_bookmarks_index(conn, &bookmarks)).await
+}
+
+/// Поиск по закладкам
+pub async fn search_bookmarks(
+    query: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<SearchResults<BookmarkSearchHit>, String> {
+    let fts_query = match build_fts_query(&query) {
+        Some(q) => q,
+        None => return Ok(SearchResults::empty()),
+    };
+    let limit = clamp_limit(limit);
+    let offset = offset.unwrap_or(0) as i64;
+    let bookmarks = get_bookmarks().await?;
+
+    with_history_db(move |conn| {
+        conn.execute_batch(BOOKMARKS_FTS_SCHEMA)
+            .map_err(|e| e.to_string())?;
+
+        // Индекс пуст — закладки сохранены до появления поиска, заполняем его сейчас
+        let indexed: i64 = conn
+            .query_row("SELECT COUNT(*) FROM bookmarks_fts", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if indexed == 0 && bookmarks.iter().any(|b| b.kind == BookmarkKind::Bookmark) {
+            write_bookmarks_index(conn, &bookmarks)?;
+        }
+
+        let total: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM bookmarks_fts WHERE bookmarks_fts MATCH ?1",
+                params![fts_query],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut stmt = conn
+            .prepare(
+                "
+            SELECT id, highlight(bookmarks_fts, 1, ?4, ?5), highlight(bookmarks_fts, 2, ?4, ?5),
+                   bm25(bookmarks_fts, 0.0, 1.0, 2.0) AS rank
+            FROM bookmarks_fts
+            WHERE bookmarks_fts MATCH ?1
+            ORDER BY rank
+            LIMIT ?2 OFFSET ?3
+        ",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map(
+                params![
+                    fts_query,
+                    limit,
+                    offset,
+                    MARK_START.to_string(),
+                    MARK_END.to_string()
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, f64>(3)?,
+                    ))
+                },
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            let (id, marked_url, marked_title, rank) = row.map_err(|e| e.to_string())?;
+            // Индекс может отставать от файла закладок — пропускаем удалённые
+            let bookmark = match bookmarks.iter().find(|b| b.id == id) {
+                Some(b) => b.clone(),
+                None => continue,
+            };
+            let (_, url_highlights) = split_highlight(&marked_url);
+            let (_, title_highlights) = split_highlight(&marked_title);
+
+            items.push(BookmarkSearchHit {
+                bookmark,
+                rank,
+                title_highlights,
+                url_highlights,
+            });
+        }
+
+        Ok(SearchResults { items, total })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::history::{insert_visit, open_history_db};
+    use crate::test_support::TempDir;
+
+    fn visit(conn: &mut Connection, id: &str, url: &str, title: &str, visited_at: i64) {
+        let entry = HistoryEntry {
+            id: id.to_string(),
+            url: url.to_string(),
+            title: title.to_string(),
+            favicon: None,
+            visited_at,
+            visit_count: 1,
+            transition: Transition::Link,
+        };
+        let tx = conn.transaction().unwrap();
+        insert_visit(&tx, &entry).unwrap();
+        tx.commit().unwrap();
+    }
+
+    fn search(conn: &Connection, input: &str, from: i64, to: i64) -> SearchResults<HistorySearchHit> {
+        find_history(conn, &build_fts_query(input).unwrap(), 50, 0, from, to).unwrap()
+    }
+
+    fn ranges(highlights: &[HighlightRange]) -> Vec<(usize, usize)> {
+        highlights.iter().map(|r| (r.start, r.end)).collect()
+    }
+
+    #[test]
+    fn quotes_every_term() {
+        let cases = [
+            ("rust", Some(r#""rust"*"#)),
+            ("rust  book", Some(r#""rust"* "book"*"#)),
+            (r#"say "hi""#, Some(r#""say"* """hi"""*"#)),
+            ("rust*", Some(r#""rust*"*"#)),
+            ("rust AND go", Some(r#""rust"* "AND"* "go"*"#)),
+            ("NEAR(rust go)", Some(r#""NEAR(rust"* "go)"*"#)),
+            ("-rust title:go", Some(r#""-rust"* "title:go"*"#)),
+            ("   ", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(build_fts_query(input).as_deref(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn operators_typed_by_user_are_plain_words() {
+        let dir = TempDir::new("search");
+        let mut conn = open_history_db(&dir).unwrap();
+        visit(&mut conn, "v1", "https://rust.example/", "Rust and Go", 1000);
+
+        // Совпадают как обычные слова, а не как синтаксис FTS5
+        for input in ["AND", "rust*", "\"rust", "-rust", "go AND rust", "^rust"] {
+            assert_eq!(search(&conn, input, i64::MIN, i64::MAX).total, 1, "{}", input);
+        }
+        // Не совпадают ни с чем, но и не ломают запрос
+        for input in ["NEAR(rust go)", "title:go", "rust OR", "\"", "*"] {
+            assert_eq!(search(&conn, input, i64::MIN, i64::MAX).total, 0, "{}", input);
+        }
+    }
+
+    #[test]
+    fn highlights_in_utf16_units() {
+        let dir = TempDir::new("search");
+        let mut conn = open_history_db(&dir).unwrap();
+        visit(&mut conn, "v1", "https://ru.example/", "Привет, мир", 1000);
+        visit(&mut conn, "v2", "https://emoji.example/", "😀 Rust", 1000);
+        visit(&mut conn, "v3", "https://cafe.example/", "Café crème", 1000);
+
+        let hits = search(&conn, "мир", i64::MIN, i64::MAX).items;
+        assert_eq!(hits[0].entry.title, "Привет, мир");
+        assert_eq!(ranges(&hits[0].title_highlights), vec![(8, 11)]);
+
+        // Эмодзи занимает две единицы UTF-16
+        let hits = search(&conn, "rust", i64::MIN, i64::MAX).items;
+        assert_eq!(ranges(&hits[0].title_highlights), vec![(3, 7)]);
+
+        // Совпадение без диакритики подсвечивает слово как оно записано
+        let hits = search(&conn, "creme", i64::MIN, i64::MAX).items;
+        assert_eq!(hits[0].entry.title, "Café crème");
+        assert_eq!(ranges(&hits[0].title_highlights), vec![(5, 10)]);
+        assert!(hits[0].url_highlights.is_empty());
+    }
+
+    #[test]
+    fn total_counts_all_pages() {
+        let dir = TempDir::new("search");
+        let mut conn = open_history_db(&dir).unwrap();
+        for i in 0..5 {
+            visit(&mut conn, &format!("v{}", i), &format!("https://{}.example/", i), &format!("Page {}", i), 1000 + i);
+        }
+        let query = build_fts_query("page").unwrap();
+
+        let first = find_history(&conn, &query, 2, 0, i64::MIN, i64::MAX).unwrap();
+        assert_eq!((first.items.len(), first.total), (2, 5));
+
+        let last = find_history(&conn, &query, 2, 4, i64::MIN, i64::MAX).unwrap();
+        assert_eq!((last.items.len(), last.total), (1, 5));
+
+        let past_end = find_history(&conn, &query, 2, 10, i64::MIN, i64::MAX).unwrap();
+        assert_eq!((past_end.items.len(), past_end.total), (0, 5));
+    }
+
+    #[test]
+    fn filters_by_visit_time() {
+        let dir = TempDir::new("search");
+        let mut conn = open_history_db(&dir).unwrap();
+        visit(&mut conn, "a1", "https://a.example/", "Site A", 1000);
+        visit(&mut conn, "a2", "https://a.example/", "Site A", 3000);
+        visit(&mut conn, "b1", "https://b.example/", "Site B", 2000);
+
+        let found = |from, to| -> Vec<(String, i64)> {
+            let mut hits: Vec<(String, i64)> = search(&conn, "site", from, to)
+                .items
+                .into_iter()
+                .map(|hit| (hit.entry.id, hit.entry.visited_at))
+                .collect();
+            hits.sort();
+            hits
+        };
+
+        assert_eq!(found(1500, 2500), vec![("b1".to_string(), 2000)]);
+        // Посещение и время — последние в интервале, а не вообще
+        assert_eq!(found(i64::MIN, 1500), vec![("a1".to_string(), 1000)]);
+        assert_eq!(found(2500, i64::MAX), vec![("a2".to_string(), 3000)]);
+        assert_eq!(found(i64::MIN, i64::MAX), vec![("a2".to_string(), 3000), ("b1".to_string(), 2000)]);
+        assert!(found(4000, 5000).is_empty());
+        assert_eq!(search(&conn, "site", 4000, 5000).total, 0);
+    }
+}
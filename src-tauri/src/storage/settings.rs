@@ -1,24 +1,497 @@
+//! Настройки браузера
+//!
+//! Повторяет интерфейс `Settings` фронтенда. Отсутствующие ключи получают значения
+//! по умолчанию, неизвестные (например, добавленные более новой версией фронтенда)
+//! сохраняются как есть в `extra`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use super::atomic::{read_json, write_json};
 use super::ensure_data_dir;
 
-pub async fn get_settings() -> Result<serde_json::Value, String> {
-    let path = ensure_data_dir()?.join("settings.json");
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEngine {
+    #[default]
+    Google,
+    Duckduckgo,
+    Bing,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FontFamily {
+    #[default]
+    System,
+    Inter,
+    Roboto,
+    Jetbrains,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderRadius {
+    None,
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SidebarPosition {
+    Left,
+    #[default]
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SidebarStyle {
+    #[default]
+    Default,
+    Compact,
+    Minimal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabPosition {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabStyle {
+    #[default]
+    Default,
+    Compact,
+    Pills,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabCloseButton {
+    #[default]
+    Hover,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClockFormat {
+    #[serde(rename = "12h")]
+    H12,
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuickSitesLayout {
+    #[default]
+    Grid,
+    List,
+    Compact,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Ru,
+    En,
+    Es,
+    Fr,
+    De,
+}
+
+/// Правило выбора папки для загрузки по типу файла
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadRule {
+    /// MIME-типы (`application/pdf`, `image/*`) или расширения (`pdf`, `.tar.gz`) через запятую
+    #[serde(rename = "match")]
+    pub pattern: String,
+    /// Папка: абсолютный путь или путь от домашнего каталога (`~/Pictures`)
+    pub folder: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Поиск
+    #[serde(rename = "searchEngine")]
+    pub search_engine: SearchEngine,
+
+    // Внешний вид
+    pub theme: Theme,
+    #[serde(rename = "accentColor")]
+    pub accent_color: String,
+    #[serde(rename = "fontSize")]
+    pub font_size: u32,
+    #[serde(rename = "fontFamily")]
+    pub font_family: FontFamily,
+    #[serde(rename = "borderRadius")]
+    pub border_radius: BorderRadius,
+
+    // Сайдбар
+    #[serde(rename = "sidebarPosition")]
+    pub sidebar_position: SidebarPosition,
+    #[serde(rename = "sidebarStyle")]
+    pub sidebar_style: SidebarStyle,
+    #[serde(rename = "sidebarAutoHide")]
+    pub sidebar_auto_hide: bool,
+    #[serde(rename = "showSidebarQuickSites")]
+    pub show_sidebar_quick_sites: bool,
+    #[serde(rename = "showSidebarWorkspaces")]
+    pub show_sidebar_workspaces: bool,
+    #[serde(rename = "showSidebarNavigation")]
+    pub show_sidebar_navigation: bool,
+
+    // Вкладки
+    #[serde(rename = "tabPosition")]
+    pub tab_position: TabPosition,
+    #[serde(rename = "tabStyle")]
+    pub tab_style: TabStyle,
+    #[serde(rename = "showTabPreviews")]
+    pub show_tab_previews: bool,
+    #[serde(rename = "showTabFavicons")]
+    pub show_tab_favicons: bool,
+    #[serde(rename = "tabCloseButton")]
+    pub tab_close_button: TabCloseButton,
+
+    // Стартовая страница
+    #[serde(rename = "startPageBackground")]
+    pub start_page_background: String,
+    #[serde(rename = "wallpaperUrl")]
+    pub wallpaper_url: String,
+    #[serde(rename = "wallpaperBlur")]
+    pub wallpaper_blur: u32,
+    #[serde(rename = "wallpaperDim")]
+    pub wallpaper_dim: u32,
+    #[serde(rename = "showWeather")]
+    pub show_weather: bool,
+    #[serde(rename = "showQuotes")]
+    pub show_quotes: bool,
+    #[serde(rename = "showTodos")]
+    pub show_todos: bool,
+    #[serde(rename = "showClock")]
+    pub show_clock: bool,
+    #[serde(rename = "clockFormat")]
+    pub clock_format: ClockFormat,
+    #[serde(rename = "showSearchOnStartPage")]
+    pub show_search_on_start_page: bool,
+    #[serde(rename = "showQuickSitesOnStartPage")]
+    pub show_quick_sites_on_start_page: bool,
+    #[serde(rename = "quickSitesLayout")]
+    pub quick_sites_layout: QuickSitesLayout,
+
+    // Приватность и безопасность
+    #[serde(rename = "adBlockEnabled")]
+    pub ad_block_enabled: bool,
+    #[serde(rename = "trackingProtection")]
+    pub tracking_protection: bool,
+    #[serde(rename = "httpsOnly")]
+    pub https_only: bool,
+    #[serde(rename = "clearDataOnExit")]
+    pub clear_data_on_exit: bool,
+
+    // Производительность
+    #[serde(rename = "hardwareAcceleration")]
+    pub hardware_acceleration: bool,
+    #[serde(rename = "tabSuspension")]
+    pub tab_suspension: bool,
+    /// Через сколько минут неактивности вкладка замораживается
+    #[serde(rename = "tabSuspensionTimeout")]
+    pub tab_suspension_timeout: u32,
+    #[serde(rename = "preloadPages")]
+    pub preload_pages: bool,
+    /// Сколько загрузок идёт одновременно; остальные ждут в очереди
+    #[serde(rename = "maxConcurrentDownloads")]
+    pub max_concurrent_downloads: u32,
+    /// Сколько соединений открывать для одного большого файла (1 — без разбиения)
+    #[serde(rename = "downloadConnections")]
+    pub download_connections: u32,
+    /// Общий лимит скорости загрузок, байт/с (0 — без ограничений)
+    #[serde(rename = "downloadSpeedLimit")]
+    pub download_speed_limit: u64,
+    /// Загрузки больше этого размера (байт) начинаются только после
+    /// подтверждения (0 — не спрашивать)
+    #[serde(rename = "downloadConfirmSize")]
+    pub download_confirm_size: u64,
+
+    // Дополнительно
+    #[serde(rename = "showBookmarksBar")]
+    pub show_bookmarks_bar: bool,
+    #[serde(rename = "readerModeEnabled")]
+    pub reader_mode_enabled: bool,
+    #[serde(rename = "smoothScrolling")]
+    pub smooth_scrolling: bool,
+    #[serde(rename = "animationsEnabled")]
+    pub animations_enabled: bool,
+    #[serde(rename = "soundEnabled")]
+    pub sound_enabled: bool,
+    #[serde(rename = "notificationsEnabled")]
+    pub notifications_enabled: bool,
+    /// Папка для загрузок (пустая строка — системная папка "Загрузки")
+    #[serde(rename = "downloadPath")]
+    pub download_path: String,
+    /// Спрашивать, куда сохранить каждый файл
+    #[serde(rename = "askWhereToSave")]
+    pub ask_where_to_save: bool,
+    /// Папки по типу файла; срабатывает первое подходящее правило
+    #[serde(rename = "downloadRules")]
+    pub download_rules: Vec<DownloadRule>,
+    pub language: Language,
+    #[serde(rename = "showWelcomeOnNextLaunch")]
+    pub show_welcome_on_next_launch: bool,
+
+    /// Ключи, о которых бэкенд не знает — сохраняются без изменений
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            search_engine: SearchEngine::Google,
 
-    if !path.exists() {
-        return Ok(serde_json::json!({}));
+            theme: Theme::Dark,
+            accent_color: "#7c3aed".to_string(),
+            font_size: 14,
+            font_family: FontFamily::System,
+            border_radius: BorderRadius::Medium,
+
+            sidebar_position: SidebarPosition::Right,
+            sidebar_style: SidebarStyle::Default,
+            sidebar_auto_hide: false,
+            show_sidebar_quick_sites: true,
+            show_sidebar_workspaces: true,
+            show_sidebar_navigation: true,
+
+            tab_position: TabPosition::Top,
+            tab_style: TabStyle::Default,
+            show_tab_previews: true,
+            show_tab_favicons: true,
+            tab_close_button: TabCloseButton::Hover,
+
+            start_page_background: "gradient".to_string(),
+            wallpaper_url: "/walpaper1.jpg".to_string(),
+            wallpaper_blur: 0,
+            wallpaper_dim: 20,
+            show_weather: true,
+            show_quotes: false,
+            show_todos: false,
+            show_clock: true,
+            clock_format: ClockFormat::H24,
+            show_search_on_start_page: true,
+            show_quick_sites_on_start_page: true,
+            quick_sites_layout: QuickSitesLayout::Grid,
+
+            ad_block_enabled: true,
+            tracking_protection: true,
+            https_only: false,
+            clear_data_on_exit: false,
+
+            hardware_acceleration: true,
+            tab_suspension: true,
+            tab_suspension_timeout: 30,
+            preload_pages: false,
+            max_concurrent_downloads: 3,
+            download_connections: 4,
+            download_speed_limit: 0,
+            download_confirm_size: 0,
+
+            show_bookmarks_bar: true,
+            reader_mode_enabled: false,
+            smooth_scrolling: true,
+            animations_enabled: true,
+            sound_enabled: true,
+            notifications_enabled: true,
+            download_path: String::new(),
+            ask_where_to_save: false,
+            download_rules: Vec::new(),
+            language: Language::Ru,
+            show_welcome_on_next_launch: false,
+
+            extra: Map::new(),
+        }
     }
+}
 
-    let content = tokio::fs::read_to_string(path)
-        .await
-        .map_err(|e| e.to_string())?;
+fn check_range(name: &str, value: u32, min: u32, max: u32) -> Result<(), String> {
+    if value < min || value > max {
+        return Err(format!("{} must be between {} and {}, got {}", name, min, max, value));
+    }
+    Ok(())
+}
 
-    serde_json::from_str(&content).map_err(|e| e.to_string())
+/// `#rgb` или `#rrggbb`
+fn is_hex_color(value: &str) -> bool {
+    match value.strip_prefix('#') {
+        Some(hex) => (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
 }
 
-pub async fn set_settings(settings: serde_json::Value) -> Result<(), String> {
+impl Settings {
+    /// Проверить диапазоны значений (те же, что у ползунков в окне настроек)
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("fontSize", self.font_size, 12, 18)?;
+        check_range("wallpaperBlur", self.wallpaper_blur, 0, 30)?;
+        check_range("wallpaperDim", self.wallpaper_dim, 0, 80)?;
+        check_range("tabSuspensionTimeout", self.tab_suspension_timeout, 5, 120)?;
+        check_range("maxConcurrentDownloads", self.max_concurrent_downloads, 1, 10)?;
+        check_range("downloadConnections", self.download_connections, 1, 16)?;
+        if self.download_speed_limit != 0 && self.download_speed_limit < 1024 {
+            return Err(format!(
+                "downloadSpeedLimit must be 0 (unlimited) or at least 1024 bytes/s, got {}",
+                self.download_speed_limit
+            ));
+        }
+
+        if !is_hex_color(&self.accent_color) {
+            return Err(format!("accentColor must be a hex color, got {:?}", self.accent_color));
+        }
+        if !self.download_path.is_empty() && !Path::new(&self.download_path).is_absolute() {
+            return Err(format!("downloadPath must be an absolute path, got {:?}", self.download_path));
+        }
+        for rule in &self.download_rules {
+            if rule.pattern.split(',').all(|p| p.trim().is_empty()) {
+                return Err("downloadRules: match must not be empty".to_string());
+            }
+            let home_relative = rule.folder == "~" || rule.folder.starts_with("~/") || rule.folder.starts_with("~\\");
+            if !home_relative && !Path::new(&rule.folder).is_absolute() {
+                return Err(format!("downloadRules: folder must be an absolute path or start with ~/, got {:?}", rule.folder));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Собрать настройки из сохранённого JSON. Некорректные значения
+    /// (не тот тип, вне диапазона) заменяются значениями по умолчанию,
+    /// остальные ключи — включая неизвестные — сохраняются.
+    pub fn from_stored(value: Value) -> Self {
+        fn parse(map: &Map<String, Value>) -> Option<Settings> {
+            serde_json::from_value::<Settings>(Value::Object(map.clone()))
+                .ok()
+                .filter(|s| s.validate().is_ok())
+        }
+
+        let stored = match value {
+            Value::Object(map) => map,
+            _ => return Self::default(),
+        };
+        if let Some(settings) = parse(&stored) {
+            return settings;
+        }
+
+        let mut accepted = Map::new();
+        for (key, value) in stored {
+            let mut candidate = accepted.clone();
+            candidate.insert(key.clone(), value.clone());
+            if parse(&candidate).is_some() {
+                accepted.insert(key, value);
+            }
+        }
+
+        parse(&accepted).unwrap_or_default()
+    }
+
+    /// Ключи (в именах фронтенда), значения которых отличаются от `other`
+    pub fn changed_keys(&self, other: &Settings) -> Vec<String> {
+        let to_map = |s: &Settings| match serde_json::to_value(s) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        let old = to_map(other);
+        let new = to_map(self);
+
+        let mut changed: Vec<String> = new
+            .iter()
+            .filter(|(key, value)| old.get(*key) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        changed.extend(old.keys().filter(|key| !new.contains_key(*key)).cloned());
+        changed
+    }
+}
+
+/// Содержимое события `settings-changed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsChanged {
+    pub settings: Settings,
+    /// Изменившиеся ключи, например `["httpsOnly", "downloadPath"]`
+    pub changed: Vec<String>,
+}
+
+pub async fn get_settings() -> Result<Settings, String> {
     let path = ensure_data_dir()?.join("settings.json");
-    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
 
-    tokio::fs::write(path, content)
-        .await
-        .map_err(|e| e.to_string())
+    Ok(read_json(&path)
+        .await?
+        .map(Settings::from_stored)
+        .unwrap_or_default())
+}
+
+/// Проверить и сохранить настройки. Возвращает набор изменений для события `settings-changed`.
+pub async fn set_settings(settings: Settings) -> Result<SettingsChanged, String> {
+    settings.validate()?;
+
+    let previous = get_settings().await?;
+    let path = ensure_data_dir()?.join("settings.json");
+    write_json(&path, &settings).await?;
+
+    Ok(SettingsChanged {
+        changed: settings.changed_keys(&previous),
+        settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Языки из списка в окне настроек (`AdvancedSettings.tsx`)
+    const UI_LANGUAGES: [&str; 5] = ["ru", "en", "es", "fr", "de"];
+
+    #[test]
+    fn round_trips_every_ui_language() {
+        for code in UI_LANGUAGES {
+            // Так настройки приходят в set_settings
+            let mut sent = serde_json::to_value(Settings::default()).unwrap();
+            sent["language"] = Value::from(code);
+            let settings: Settings = serde_json::from_value(sent).unwrap();
+            assert_eq!(serde_json::to_value(settings.language).unwrap(), code);
+
+            // Сохранённый язык не сбрасывается при чтении
+            let stored = Settings::from_stored(serde_json::json!({ "language": code }));
+            assert_eq!(stored.language, settings.language, "{}", code);
+        }
+    }
+
+    #[test]
+    fn unknown_language_falls_back_to_default() {
+        let stored = Settings::from_stored(serde_json::json!({ "language": "xx", "fontSize": 16 }));
+        assert_eq!(stored.language, Language::default());
+        assert_eq!(stored.font_size, 16);
+    }
 }
@@ -0,0 +1,242 @@
+//! Общее для тестов: временные каталоги и локальный HTTP-сервер
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Временный каталог; удаляется, даже если тест упал
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("axion-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Запрос к тестовому серверу
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// Имена в нижнем регистре
+    pub headers: HashMap<String, String>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// Ответ тестового сервера; `Content-Length` и `Connection: close` добавляются сами
+#[derive(Debug, Clone)]
+pub struct Reply {
+    status: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    stall_after: Option<usize>,
+}
+
+impl Reply {
+    /// `status` — код и пояснение: `"200 OK"`
+    pub fn new(status: &str) -> Self {
+        Self {
+            status: status.to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            stall_after: None,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Отдать столько байт тела и замолчать, не закрывая соединение
+    pub fn stall_after(mut self, bytes: usize) -> Self {
+        self.stall_after = Some(bytes);
+        self
+    }
+}
+
+pub struct TestServer {
+    base: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    /// `http://127.0.0.1:<порт><path>`
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    /// Все запросы по порядку
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Запустить сервер. `respond` получает запрос и его номер (с нуля)
+pub async fn serve(respond: impl Fn(&Request, usize) -> Reply + Send + Sync + 'static) -> TestServer {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let respond = Arc::new(respond);
+
+    let log = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(_) => return,
+            };
+            let log = log.clone();
+            let respond = respond.clone();
+
+            tokio::spawn(async move {
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => raw.extend_from_slice(&buf[..n]),
+                    }
+                }
+
+                let text = String::from_utf8_lossy(&raw).to_string();
+                let mut request_line = text.lines().next().unwrap_or_default().split_whitespace();
+                let request = Request {
+                    method: request_line.next().unwrap_or_default().to_string(),
+                    path: request_line.next().unwrap_or_default().to_string(),
+                    headers: text
+                        .lines()
+                        .skip(1)
+                        .filter_map(|line| line.split_once(':'))
+                        .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+                        .collect(),
+                };
+                let index = {
+                    let mut log = log.lock().unwrap();
+                    log.push(request.clone());
+                    log.len() - 1
+                };
+                let reply = respond(&request, index);
+
+                let mut head = format!("HTTP/1.1 {}\r\n", reply.status);
+                for (name, value) in &reply.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str(&format!("Content-Length: {}\r\n", reply.body.len()));
+                head.push_str("Connection: close\r\n\r\n");
+
+                let _ = socket.write_all(head.as_bytes()).await;
+                if request.method == "HEAD" {
+                    return;
+                }
+                match reply.stall_after {
+                    Some(limit) => {
+                        let _ = socket.write_all(&reply.body[..limit.min(reply.body.len())]).await;
+                        let _ = socket.flush().await;
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                    }
+                    None => {
+                        let _ = socket.write_all(&reply.body).await;
+                    }
+                }
+            });
+        }
+    });
+
+    TestServer { base, requests }
+}
+
+/// Файл, который отдаёт [`serve_file`]
+#[derive(Clone, Default)]
+pub struct FileConfig {
+    pub body: Vec<u8>,
+    pub etag: Option<String>,
+    /// Отвечать на `Range` частью файла (иначе всегда 200)
+    pub honor_ranges: bool,
+    /// Каждый ответ зависает после этого числа байт тела
+    pub stall_after: Option<usize>,
+    /// Строка статуса, которой сервер отвечает на первый запрос (без тела)
+    pub fail_first: Option<&'static str>,
+}
+
+/// Сервер, отдающий один файл по любому пути: `Range` (`bytes=a-` и
+/// `bytes=a-b`), `If-Range` по ETag, 416 за концом файла
+pub async fn serve_file(config: FileConfig) -> TestServer {
+    serve(move |request, index| {
+        if let (Some(status), 0) = (config.fail_first, index) {
+            return Reply::new(status).header("Retry-After", "1");
+        }
+
+        let len = config.body.len();
+        let range = request
+            .header("range")
+            .and_then(|r| r.strip_prefix("bytes="))
+            .and_then(|r| r.split_once('-'))
+            .and_then(|(start, end)| {
+                let start = start.parse::<usize>().ok()?;
+                let end = end.parse::<usize>().map(|end| end + 1).unwrap_or(len);
+                Some((start, end.min(len)))
+            })
+            .filter(|_| config.honor_ranges)
+            .filter(|_| match request.header("if-range") {
+                Some(if_range) => config.etag.as_deref() == Some(if_range),
+                None => true,
+            });
+
+        let mut reply = match range {
+            Some((start, _)) if start >= len => Reply::new("416 Range Not Satisfiable")
+                .header("Content-Range", format!("bytes */{}", len)),
+            Some((start, end)) => Reply::new("206 Partial Content")
+                .header("Content-Range", format!("bytes {}-{}/{}", start, end - 1, len))
+                .body(&config.body[start..end]),
+            None => Reply::new("200 OK").body(config.body.clone()),
+        };
+        if let Some(etag) = &config.etag {
+            reply = reply.header("ETag", etag.clone());
+        }
+        if config.honor_ranges {
+            reply = reply.header("Accept-Ranges", "bytes");
+        }
+        if let Some(limit) = config.stall_after {
+            reply = reply.stall_after(limit);
+        }
+        reply
+    })
+    .await
+}
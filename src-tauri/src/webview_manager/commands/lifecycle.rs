@@ -2,9 +2,8 @@
 
 use tauri::{AppHandle, Manager, WebviewUrl, WebviewBuilder, LogicalPosition, LogicalSize, Emitter};
 use tauri::webview::{PageLoadEvent, DownloadEvent};
-use std::path::PathBuf;
 use crate::webview_manager::types::{WebViewUpdateEvent, WebViewBounds};
-use crate::webview_manager::polling::{poll_webview_state, extract_title_from_url, extract_filename_from_url};
+use crate::webview_manager::polling::{poll_webview_state, extract_title_from_url};
 use crate::webview_manager::CHROME_USER_AGENT;
 
 /// Создание нового нативного WebView для вкладки (встроенного в главное окно)
@@ -52,8 +51,10 @@ pub async fn create_webview(
     let tab_id_title = id.clone();
     let app_title = app.clone();
     
+    let tab_id_download = id.clone();
+    
     // Создаём WebView с оптимизированными настройками
-    let builder = WebviewBuilder::new(&webview_id, webview_url)
+    let mut builder = WebviewBuilder::new(&webview_id, webview_url)
         // Устанавливаем User-Agent для совместимости с Google
         .user_agent(CHROME_USER_AGENT)
         // Включаем аппаратное ускорение и оптимизации
@@ -142,117 +143,44 @@ pub async fn create_webview(
         // Обработчик загрузок - перехватываем загрузки из WebView
         .on_download({
             let app_download = app.clone();
-            move |_webview, event| {
+            // Загрузки, которые сохраняет сам WebView: адрес → id
+            let native_downloads = std::sync::Mutex::new(std::collections::HashMap::<String, String>::new());
+            move |webview, event| {
                 match event {
                     DownloadEvent::Requested { url, destination } => {
-                        // Конвертируем Url в String
-                        let url_str = url.to_string();
-                        
-                        // Получаем папку загрузок
-                        let downloads_dir = dirs::download_dir()
-                            .unwrap_or_else(|| PathBuf::from("."));
-                        
-                        // Извлекаем имя файла из URL
-                        let filename = extract_filename_from_url(&url_str);
-                        let save_path = downloads_dir.join(&filename);
-                        
-                        // Устанавливаем путь сохранения
-                        *destination = save_path.clone();
-                        
-                        // Генерируем ID загрузки
-                        let download_id = format!("dl_{}", uuid::Uuid::new_v4().to_string().replace("-", "")[..12].to_string());
-                        
-                        // Создаём объект загрузки
-                        let download = serde_json::json!({
-                            "id": download_id,
-                            "filename": filename,
-                            "url": url_str,
-                            "totalBytes": -1,
-                            "receivedBytes": 0,
-                            "state": "progressing",
-                            "startTime": chrono::Utc::now().timestamp_millis(),
-                            "savePath": save_path.to_string_lossy().to_string(),
-                            "speed": 0,
-                            "mimeType": serde_json::Value::Null
-                        });
+                        // WebView уже выбрал имя по ответу сервера и атрибуту download
+                        let suggested = destination.file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .filter(|name| !name.is_empty());
                         
-                        // Отправляем события о начале загрузки
-                        let _ = app_download.emit("download-started", &download);
-                        let _ = app_download.emit("download-update", &download);
-                        
-                        // Сохраняем информацию о загрузке для отслеживания завершения
-                        // Используем URL как ключ для связи с событием Finished
-                        {
-                            let state = app_download.state::<crate::AppState>();
-                            if let Ok(mut downloads) = state.downloads.lock() {
-                                downloads.insert(url_str.clone(), crate::downloads::Download {
-                                    id: download_id,
-                                    filename: filename.clone(),
-                                    url: url_str.clone(),
-                                    total_bytes: -1,
-                                    received_bytes: 0,
-                                    state: "progressing".to_string(),
-                                    start_time: chrono::Utc::now().timestamp_millis(),
-                                    save_path: Some(save_path.to_string_lossy().to_string()),
-                                    speed: 0,
-                                    mime_type: None,
-                                });
-                            };
+                        if matches!(url.scheme(), "http" | "https") {
+                            // Скачивает менеджер загрузок: очередь, прогресс, пауза и докачка
+                            let app = app_download.clone();
+                            let tab_id = tab_id_download.clone();
+                            let url = url.to_string();
+                            tauri::async_runtime::spawn(async move {
+                                let _ = crate::downloads::start_webview_download(app, webview, tab_id, url, suggested).await;
+                            });
+                            return false; // Отменяем загрузку самим WebView
                         }
                         
-                        true // Разрешаем загрузку
-                    }
-                    DownloadEvent::Finished { url, path, success } => {
-                        // Конвертируем Url в String
-                        let url_str = url.to_string();
-                        
-                        // Получаем информацию о загрузке по URL
-                        let download_info = {
-                            let state = app_download.state::<crate::AppState>();
-                            let result = if let Ok(mut downloads) = state.downloads.lock() {
-                                downloads.remove(&url_str)
-                            } else {
-                                None
-                            };
-                            result
-                        };
-                        
-                        if let Some(mut dl) = download_info {
-                            dl.state = if success { "completed".to_string() } else { "interrupted".to_string() };
-                            
-                            // Получаем размер файла если загрузка успешна
-                            if success {
-                                if let Some(ref p) = path {
-                                    if let Ok(metadata) = std::fs::metadata(p) {
-                                        dl.total_bytes = metadata.len() as i64;
-                                        dl.received_bytes = metadata.len() as i64;
-                                    }
+                        // blob: и data: доступны только странице — сохраняет WebView
+                        match crate::downloads::begin_native_download(&app_download, &webview, tab_id_download.clone(), url.as_str(), suggested) {
+                            Ok((download_id, save_path)) => {
+                                if let Ok(mut ids) = native_downloads.lock() {
+                                    ids.insert(url.to_string(), download_id);
                                 }
+                                *destination = save_path;
+                                true
                             }
-                            
-                            // Отправляем события о завершении
-                            let _ = app_download.emit("download-update", &dl);
-                            let _ = app_download.emit("download-completed", &dl);
-                            
-                            // Сохраняем в историю загрузок
-                            let dl_clone = dl.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Ok(mut downloads) = crate::downloads::get_downloads().await {
-                                    // Проверяем, нет ли уже такой загрузки
-                                    if !downloads.iter().any(|d| d.id == dl_clone.id) {
-                                        downloads.insert(0, dl_clone);
-                                        let _ = crate::downloads::save_downloads(downloads).await;
-                                    } else {
-                                        // Обновляем существующую
-                                        if let Some(existing) = downloads.iter_mut().find(|d| d.id == dl_clone.id) {
-                                            *existing = dl_clone;
-                                        }
-                                        let _ = crate::downloads::save_downloads(downloads).await;
-                                    }
-                                }
-                            });
+                            Err(_) => false,
                         }
-                        
+                    }
+                    DownloadEvent::Finished { url, path, success } => {
+                        // id нужен, только если WebView не сообщит путь файла
+                        let download_id = native_downloads.lock().ok()
+                            .and_then(|mut ids| ids.remove(url.as_str()));
+                        crate::downloads::finish_native_download(&app_download, download_id.as_deref(), path.as_deref(), success);
                         true
                     }
                     _ => true,
@@ -341,6 +269,11 @@ pub async fn create_webview(
                 }
             };
         });
+
+    // Cookies и хранилище сайтов у каждого профиля свои
+    if let Some(data_dir) = crate::storage::webview_data_dir()? {
+        builder = builder.data_directory(data_dir);
+    }
     
     main_window.add_child(
         builder,
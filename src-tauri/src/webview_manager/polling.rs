@@ -98,26 +98,6 @@ pub async fn poll_webview_state(app: AppHandle, id: String) {
     }
 }
 
-/// Извлекает имя файла из URL для загрузки
-pub fn extract_filename_from_url(url: &str) -> String {
-    if let Ok(parsed) = url::Url::parse(url) {
-        if let Some(segments) = parsed.path_segments() {
-            if let Some(last) = segments.last() {
-                let decoded = urlencoding::decode(last).unwrap_or_else(|_| last.into());
-                if !decoded.is_empty() && decoded != "/" {
-                    // Проверяем что это похоже на имя файла (есть расширение)
-                    if decoded.contains('.') {
-                        return decoded.to_string();
-                    }
-                }
-            }
-        }
-    }
-    
-    // Fallback - генерируем имя с timestamp
-    format!("download_{}", chrono::Utc::now().timestamp())
-}
-
 /// Извлекает читаемое название из URL (используется как fallback)
 pub fn extract_title_from_url(url: &str) -> String {
     if let Ok(parsed) = url::Url::parse(url) {
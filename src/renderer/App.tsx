@@ -9,6 +9,7 @@ import AppModals from './components/AppModals';
 import UpdateBanner from './components/UpdateBanner';
 import WelcomePage from './components/WelcomePage';
 import { extractSearchQueries } from './utils/url';
+import { mergeImportedBookmarks } from './utils/bookmarks';
 import {
   useWorkspaces,
   useNavigation,
@@ -22,6 +23,7 @@ import {
   useStartPageData,
   useTabThumbnails,
 } from './hooks';
+import { profileStorage } from './utils/profileStorage';
 import './styles/App.css';
 
 const App: React.FC = () => {
@@ -67,6 +69,19 @@ const App: React.FC = () => {
     checkFirstLaunch();
   }, []);
   
+  // Предлагаем продолжить загрузки, оборванные закрытием браузера
+  // (после загрузки настроек, чтобы сообщение было на выбранном языке)
+  useEffect(() => {
+    if (!welcomeChecked) return;
+    window.electronAPI.takeInterruptedDownloads()
+      .then(interrupted => {
+        if (interrupted.length > 0) {
+          setToastMessage(t.common.downloadsInterrupted);
+        }
+      })
+      .catch(error => console.error('Failed to load interrupted downloads:', error));
+  }, [welcomeChecked]);
+  
   // Состояния для StartPage сайтов
   const {
     hiddenSites,
@@ -184,7 +199,7 @@ const App: React.FC = () => {
 
   const handleSidebarWidthChange = useCallback((width: number) => {
     setSidebarWidth(width);
-    localStorage.setItem('sidebarWidth', width.toString());
+    profileStorage.setItem('sidebarWidth', width.toString());
   }, []);
 
   // Шорткаты
@@ -240,9 +255,7 @@ const App: React.FC = () => {
     const result = await window.electronAPI.importFromBrowser(browser);
     if (result) {
       // Объединяем закладки
-      const mergedBookmarks = [...bookmarks, ...result.bookmarks.filter(
-        (imported: { url: string }) => !bookmarks.some(b => b.url === imported.url)
-      )];
+      const mergedBookmarks = mergeImportedBookmarks(bookmarks, result.bookmarks);
       setBookmarks(mergedBookmarks);
       window.electronAPI.setBookmarks(mergedBookmarks);
       
@@ -1,4 +1,5 @@
 import React, { useState, useEffect, useRef, KeyboardEvent } from 'react';
+import { Transition } from '../../types';
 import '../../styles/components/address-bar.css';
 
 // Словарь популярных сокращений
@@ -33,7 +34,7 @@ interface AddressBarProps {
   isSecure?: boolean;
   history?: Array<{ url: string; title: string }>;
   bookmarks?: Array<{ url: string; title: string; favicon?: string }>;
-  onNavigate: (url: string) => void;
+  onNavigate: (url: string, transition?: Transition) => void;
   onBack: () => void;
   onForward: () => void;
   onReload: () => void;
@@ -86,9 +87,9 @@ const AddressBar: React.FC<AddressBarProps> = ({
       }
       
       if (targetUrl) {
-        onNavigate(targetUrl);
+        onNavigate(targetUrl, 'typed');
       } else {
-        onNavigate(trimmed);
+        onNavigate(trimmed, 'typed');
       }
       setAutoComplete('');
     } else if (e.key === 'Tab' && autoComplete) {
@@ -11,6 +11,7 @@ import {
   PresentationFileIcon,
   DownloadFileIcon,
 } from '../ZenSidebar/icons';
+import { Language } from '../../types';
 import '../../styles/components/download-indicator.css';
 
 export interface ActiveDownload {
@@ -19,7 +20,7 @@ export interface ActiveDownload {
   url: string;
   totalBytes: number;
   receivedBytes: number;
-  state: 'progressing' | 'completed' | 'cancelled' | 'interrupted';
+  state: 'queued' | 'too_large' | 'progressing' | 'retrying' | 'paused' | 'completed' | 'needs_confirmation' | 'verification_failed' | 'cancelled' | 'interrupted' | 'insufficient_space';
   startTime: number;
   savePath?: string;
   speed?: number;
@@ -28,7 +29,7 @@ export interface ActiveDownload {
 
 interface DownloadIndicatorProps {
   // language prop no longer needed but kept for interface compatibility
-  language?: 'ru' | 'en';
+  language?: Language;
 }
 
 const DownloadIndicator: React.FC<DownloadIndicatorProps> = () => {
@@ -95,7 +96,7 @@ const DownloadIndicator: React.FC<DownloadIndicatorProps> = () => {
         const index = prev.findIndex(d => d.id === download.id);
         if (index >= 0) {
           const updated = [...prev];
-          updated[index] = { ...updated[index], ...download, state: 'completed' };
+          updated[index] = { ...updated[index], ...download };
           return updated;
         }
         return prev;
@@ -110,11 +111,13 @@ const DownloadIndicator: React.FC<DownloadIndicatorProps> = () => {
     const cleanup1 = window.electronAPI.onDownloadStarted(handleDownloadStarted);
     const cleanup2 = window.electronAPI.onDownloadUpdate(handleDownloadUpdate);
     const cleanup3 = window.electronAPI.onDownloadCompleted(handleDownloadCompleted);
+    const cleanup4 = window.electronAPI.onDownloadProgress(handleDownloadUpdate);
 
     return () => {
       cleanup1();
       cleanup2();
       cleanup3();
+      cleanup4();
     };
   }, []);
 
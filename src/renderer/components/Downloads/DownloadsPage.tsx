@@ -1,6 +1,8 @@
 import React, { useState, useEffect } from 'react';
 import { useTranslation } from '../../hooks/useTranslation';
-import { DownloadFileIcon, FolderIcon, FolderOpenIcon, PauseIcon, CheckIcon } from '../ZenSidebar/icons';
+import { DownloadFileIcon, FolderIcon, FolderOpenIcon, PauseIcon, PlayIcon, ArrowUpIcon, ChevronsUpIcon, CheckIcon, XCircleIcon } from '../ZenSidebar/icons';
+import { SPEED_LIMIT_PRESETS, formatSpeedLimit } from './speedLimits';
+import { DownloadProgress, DownloadProvenance, Language } from '../../types';
 import '../../styles/components/downloads-page.css';
 
 export interface Download {
@@ -9,19 +11,31 @@ export interface Download {
   url: string;
   totalBytes: number;
   receivedBytes: number;
-  state: 'progressing' | 'completed' | 'cancelled' | 'interrupted';
+  state: 'queued' | 'too_large' | 'progressing' | 'retrying' | 'paused' | 'completed' | 'needs_confirmation' | 'verification_failed' | 'cancelled' | 'interrupted' | 'insufficient_space';
   startTime: number;
   savePath?: string;
   speed?: number;
   mimeType?: string;
+  segments?: { start: number; end: number; received: number }[];
+  speedLimit?: number | null;
+  retryAttempt?: number;
+  nextRetryAt?: number | null;
+  error?: string | null;
+  expectedDigest?: string | null;
+  digest?: string | null;
+  etaSeconds?: number | null;
+  elapsedMs?: number;
+  danger?: string | null;
+  provenance?: DownloadProvenance;
 }
 
 interface DownloadsPageProps {
-  language: 'ru' | 'en';
+  language: Language;
 }
 
 const DownloadsPage: React.FC<DownloadsPageProps> = ({ language }) => {
   const [downloads, setDownloads] = useState<Download[]>([]);
+  const [queue, setQueue] = useState<string[]>([]);
   const t = useTranslation(language);
 
   useEffect(() => {
@@ -36,23 +50,51 @@ const DownloadsPage: React.FC<DownloadsPageProps> = ({ language }) => {
         const index = prev.findIndex(d => d.id === download.id);
         if (index >= 0) {
           const updated = [...prev];
-          updated[index] = download;
+          updated[index] = { ...updated[index], ...download };
           return updated;
         }
         return [download, ...prev];
       });
     };
 
-    const cleanup = window.electronAPI.onDownloadUpdate(handleDownloadUpdate);
+    // События прогресса несут только изменившиеся поля
+    const handleDownloadProgress = (progress: DownloadProgress) => {
+      setDownloads(prev => prev.map(d => (d.id === progress.id ? { ...d, ...progress } : d)));
+    };
+
+    const cleanupUpdate = window.electronAPI.onDownloadUpdate(handleDownloadUpdate);
+    const cleanupProgress = window.electronAPI.onDownloadProgress(handleDownloadProgress);
+    return () => {
+      cleanupUpdate();
+      cleanupProgress();
+    };
+  }, []);
+
+  // Порядок очереди ожидающих загрузок
+  useEffect(() => {
+    window.electronAPI.getDownloadQueue().then(setQueue);
+    const cleanup = window.electronAPI.onDownloadQueueChanged(setQueue);
     return () => cleanup();
   }, []);
 
   const cancelDownload = (id: string) => window.electronAPI.cancelDownload(id);
-  const openDownload = (savePath: string) => window.electronAPI.openDownload(savePath);
+  const pauseDownload = (id: string) => window.electronAPI.pauseDownload(id);
+  const resumeDownload = (id: string) => window.electronAPI.resumeDownload(id);
+  const moveUp = (id: string) => {
+    const position = queue.indexOf(id);
+    if (position > 0) window.electronAPI.moveDownloadInQueue(id, position - 1);
+  };
+  const prioritize = (id: string) => window.electronAPI.prioritizeDownload(id);
+  const setSpeedLimit = (id: string, value: string) =>
+    window.electronAPI.setDownloadSpeedLimit(id, value === '' ? null : parseInt(value));
+  const resumeInterrupted = () => window.electronAPI.resumeInterruptedDownloads();
+  const hasInterrupted = downloads.some(d => d.state === 'interrupted');
+  const openDownload = (id: string) => window.electronAPI.openDownload(id);
+  const confirmDownload = (id: string) => window.electronAPI.confirmDownload(id);
   const showInFolder = (savePath: string) => window.electronAPI.showDownloadInFolder(savePath);
 
   const clearCompleted = () => {
-    setDownloads(prev => prev.filter(d => d.state === 'progressing'));
+    setDownloads(prev => prev.filter(d => d.state === 'queued' || d.state === 'too_large' || d.state === 'progressing' || d.state === 'retrying' || d.state === 'paused' || d.state === 'needs_confirmation'));
     window.electronAPI.clearCompletedDownloads();
   };
 
@@ -64,11 +106,26 @@ const DownloadsPage: React.FC<DownloadsPageProps> = ({ language }) => {
     return Math.round(bytes / Math.pow(k, i) * 100) / 100 + ' ' + sizes[i];
   };
 
+  // 75 → '1:15', 3725 → '1:02:05'
+  const formatDuration = (seconds: number) => {
+    const pad = (n: number) => String(n).padStart(2, '0');
+    const h = Math.floor(seconds / 3600);
+    const m = Math.floor(seconds / 60) % 60;
+    const s = Math.floor(seconds % 60);
+    return h > 0 ? `${h}:${pad(m)}:${pad(s)}` : `${m}:${pad(s)}`;
+  };
+
   return (
     <div className="downloads-page">
       <div className="downloads-page-header">
         <h1>{t.common.downloads}</h1>
         <div className="downloads-page-actions">
+          {hasInterrupted && (
+            <button className="downloads-clear-btn" onClick={resumeInterrupted}>
+              <PlayIcon size={16} />
+              {t.common.resumeAll}
+            </button>
+          )}
           <button className="downloads-clear-btn" onClick={clearCompleted}>
             <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2">
               <polyline points="3 6 5 6 21 6"/>
@@ -100,33 +157,115 @@ const DownloadsPage: React.FC<DownloadsPageProps> = ({ language }) => {
                 <div key={download.id} className={`download-page-item download-${download.state}`}>
                   <div className="download-page-icon"><DownloadFileIcon size={20} /></div>
                   <div className="download-page-info">
-                    <div className="download-page-filename">{download.filename}</div>
+                    <div className="download-page-filename" title={download.provenance?.finalUrl ?? download.url}>{download.filename}</div>
                     <div className="download-page-details">
-                      {download.state === 'progressing' && (
+                      {(download.state === 'progressing' || download.state === 'retrying' || download.state === 'paused') && (
                         <>
-                          <span>{formatBytes(download.receivedBytes)} / {formatBytes(download.totalBytes)}</span>
+                          <span title={download.elapsedMs ? `${t.common.elapsed} ${formatDuration(download.elapsedMs / 1000)}` : undefined}>
+                            {formatBytes(download.receivedBytes)} / {formatBytes(download.totalBytes)}
+                            {download.state === 'progressing' && download.speed ? ` - ${formatBytes(download.speed)}/s` : null}
+                            {download.state === 'progressing' && download.etaSeconds != null && ` - ${t.common.timeLeft} ${formatDuration(download.etaSeconds)}`}
+                            {download.state === 'paused' && ` - ${t.common.paused}`}
+                            {download.state === 'retrying' && (
+                              <span className="download-status-retrying" title={download.error ?? undefined}>
+                                {` - ${t.common.retrying} ${download.retryAttempt ?? 1}`}
+                              </span>
+                            )}
+                          </span>
                           <div className="download-page-progress">
-                            <div className="download-page-progress-fill" style={{ width: `${progress}%` }} />
+                            {download.state === 'progressing' && download.segments && download.segments.length > 0 && download.totalBytes > 0
+                              ? download.segments.map(segment => (
+                                  <div
+                                    key={segment.start}
+                                    className="download-page-progress-segment"
+                                    style={{
+                                      left: `${(segment.start / download.totalBytes) * 100}%`,
+                                      width: `${(segment.received / download.totalBytes) * 100}%`,
+                                    }}
+                                  />
+                                ))
+                              : <div className="download-page-progress-fill" style={{ width: `${progress}%` }} />}
                           </div>
                         </>
                       )}
+                      {download.state === 'queued' && (
+                        <span className="download-status-queued">
+                          {t.common.queued}
+                          {queue.includes(download.id) && ` #${queue.indexOf(download.id) + 1}`}
+                        </span>
+                      )}
                       {download.state === 'completed' && (
-                        <span className="download-status-completed"><CheckIcon size={14} /> {t.common.completed} - {formatBytes(download.totalBytes)}</span>
+                        <span className="download-status-completed" title={download.digest ?? undefined}><CheckIcon size={14} /> {t.common.completed} - {formatBytes(download.totalBytes)}</span>
+                      )}
+                      {download.state === 'needs_confirmation' && (
+                        <span className="download-status-needs-confirmation" title={download.error ?? undefined}>{t.common.dangerousFile}</span>
+                      )}
+                      {download.state === 'verification_failed' && (
+                        <span className="download-status-verification-failed" title={download.error ?? undefined}>{t.common.verificationFailed}</span>
                       )}
                       {download.state === 'cancelled' && <span className="download-status-cancelled">{t.common.cancelled}</span>}
-                      {download.state === 'interrupted' && <span className="download-status-interrupted">{t.common.interrupted}</span>}
+                      {download.state === 'interrupted' && <span className="download-status-interrupted" title={download.error ?? undefined}>{t.common.interrupted}</span>}
+                      {download.state === 'too_large' && (
+                        <span className="download-status-too-large">{t.common.largeDownload} - {formatBytes(download.totalBytes)}</span>
+                      )}
+                      {download.state === 'insufficient_space' && (
+                        <span className="download-status-insufficient-space" title={download.error ?? undefined}>
+                          {t.common.insufficientSpace} - {formatBytes(download.receivedBytes)} / {formatBytes(download.totalBytes)}
+                        </span>
+                      )}
                     </div>
                   </div>
                   <div className="download-page-controls">
-                    {download.state === 'progressing' && (
-                      <button onClick={() => cancelDownload(download.id)} title={t.common.cancel}><PauseIcon size={16} /></button>
+                    {download.state !== 'completed' && download.state !== 'needs_confirmation' && download.state !== 'verification_failed' && download.state !== 'cancelled' && download.state !== 'too_large' && (
+                      <select
+                        className="download-page-speed-limit"
+                        title={t.common.speedLimit}
+                        value={download.speedLimit == null ? '' : String(download.speedLimit)}
+                        onChange={(e) => setSpeedLimit(download.id, e.target.value)}
+                      >
+                        <option value="">{t.common.speedLimitDefault}</option>
+                        <option value="0">{t.common.unlimited}</option>
+                        {SPEED_LIMIT_PRESETS.map(limit => (
+                          <option key={limit} value={String(limit)}>{formatSpeedLimit(limit)}</option>
+                        ))}
+                      </select>
+                    )}
+                    {download.state === 'queued' && queue.indexOf(download.id) > 0 && (
+                      <>
+                        <button onClick={() => prioritize(download.id)} title={t.common.downloadNext}><ChevronsUpIcon size={16} /></button>
+                        <button onClick={() => moveUp(download.id)} title={t.common.moveUp}><ArrowUpIcon size={16} /></button>
+                      </>
+                    )}
+                    {(download.state === 'queued' || download.state === 'progressing' || download.state === 'retrying') && (
+                      <button onClick={() => pauseDownload(download.id)} title={t.common.pause}><PauseIcon size={16} /></button>
+                    )}
+                    {(download.state === 'paused' || download.state === 'interrupted' || download.state === 'insufficient_space') && (
+                      <button onClick={() => resumeDownload(download.id)} title={t.common.resume}><PlayIcon size={16} /></button>
+                    )}
+                    {/* Файл больше порога из настроек начнёт скачиваться только после подтверждения */}
+                    {download.state === 'too_large' && (
+                      <button onClick={() => confirmDownload(download.id)} title={t.common.startDownload}><PlayIcon size={16} /></button>
+                    )}
+                    {(download.state === 'queued' || download.state === 'too_large' || download.state === 'progressing' || download.state === 'retrying' || download.state === 'paused' || download.state === 'interrupted' || download.state === 'insufficient_space') && (
+                      <button onClick={() => cancelDownload(download.id)} title={t.common.cancel}><XCircleIcon size={16} /></button>
                     )}
                     {download.state === 'completed' && download.savePath && (
                       <>
-                        <button onClick={() => openDownload(download.savePath!)} title={t.common.open}><FolderOpenIcon size={16} /></button>
+                        <button onClick={() => openDownload(download.id)} title={t.common.open}><FolderOpenIcon size={16} /></button>
                         <button onClick={() => showInFolder(download.savePath!)} title={t.common.showInFolder}><FolderIcon size={16} /></button>
                       </>
                     )}
+                    {/* Опасный файл открывается только после подтверждения; отказ удаляет его */}
+                    {download.state === 'needs_confirmation' && (
+                      <>
+                        <button onClick={() => confirmDownload(download.id)} title={t.common.keepFile}><CheckIcon size={16} /></button>
+                        <button onClick={() => cancelDownload(download.id)} title={t.common.discardFile}><XCircleIcon size={16} /></button>
+                      </>
+                    )}
+                    {/* Файл с неверной контрольной суммой не открываем — только показываем в папке */}
+                    {download.state === 'verification_failed' && download.savePath && (
+                      <button onClick={() => showInFolder(download.savePath!)} title={t.common.showInFolder}><FolderIcon size={16} /></button>
+                    )}
                   </div>
                 </div>
               );
@@ -0,0 +1,7 @@
+// Варианты лимита скорости загрузок, байт/с
+export const SPEED_LIMIT_PRESETS = [256 * 1024, 512 * 1024, 1024 * 1024, 2 * 1024 * 1024, 5 * 1024 * 1024, 10 * 1024 * 1024];
+
+export const formatSpeedLimit = (bytesPerSec: number) =>
+  bytesPerSec >= 1024 * 1024
+    ? `${bytesPerSec / (1024 * 1024)} MB/s`
+    : `${bytesPerSec / 1024} KB/s`;
@@ -1,5 +1,5 @@
-import React, { useState, useMemo } from 'react';
-import { HistoryEntry } from '../../types';
+import React, { useState, useMemo, useEffect } from 'react';
+import { HistoryEntry, Language } from '../../types';
 import { useTranslation } from '../../hooks/useTranslation';
 import '../../styles/components/history-page.css';
 
@@ -7,18 +7,40 @@ interface HistoryPageProps {
   history: HistoryEntry[];
   onNavigate: (url: string) => void;
   onClearHistory: () => void;
-  language: 'ru' | 'en';
+  language: Language;
 }
 
 const HistoryPage: React.FC<HistoryPageProps> = ({ history, onNavigate, onClearHistory, language }) => {
   const [searchFilter, setSearchFilter] = useState('');
+  const [searchResults, setSearchResults] = useState<HistoryEntry[] | null>(null);
   const t = useTranslation(language);
 
-  const filteredHistory = useMemo(() => 
-    history.filter(h =>
-      h.title.toLowerCase().includes(searchFilter.toLowerCase()) ||
-      h.url.toLowerCase().includes(searchFilter.toLowerCase())
-    ), [history, searchFilter]);
+  // Поиск выполняется в бэкенде (FTS5), чтобы не фильтровать всю историю в React
+  useEffect(() => {
+    const query = searchFilter.trim();
+    if (!query) {
+      setSearchResults(null);
+      return;
+    }
+
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      window.electronAPI.searchHistory(query, 200)
+        .then(results => {
+          if (!cancelled) setSearchResults(results.items);
+        })
+        .catch(() => {
+          if (!cancelled) setSearchResults([]);
+        });
+    }, 150);
+
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [searchFilter]);
+
+  const filteredHistory = searchResults ?? history;
 
   // Группировка по дням
   const groupedHistory = useMemo(() => {
@@ -2,6 +2,7 @@ import React, { useState, useCallback, useEffect } from 'react';
 import { useTranslation } from '../../hooks/useTranslation';
 import { Language } from '../../types';
 import '../../styles/components/quicksites-page.css';
+import { profileStorage } from '../../utils/profileStorage';
 
 // Тип для QuickSite
 interface QuickSite {
@@ -70,7 +71,7 @@ const QuickSitesPage: React.FC<QuickSitesPageProps> = ({ language, onNavigate })
   // Загрузка сохраненных сайтов
   const [savedSites, setSavedSites] = useState<QuickSite[]>(() => {
     try {
-      const stored = localStorage.getItem(QUICK_SITES_STORAGE_KEY);
+      const stored = profileStorage.getItem(QUICK_SITES_STORAGE_KEY);
       if (stored) {
         return JSON.parse(stored);
       }
@@ -83,7 +84,7 @@ const QuickSitesPage: React.FC<QuickSitesPageProps> = ({ language, onNavigate })
   // Сохранение в localStorage и отправка события синхронизации
   const saveSites = useCallback((sites: QuickSite[]) => {
     try {
-      localStorage.setItem(QUICK_SITES_STORAGE_KEY, JSON.stringify(sites));
+      profileStorage.setItem(QUICK_SITES_STORAGE_KEY, JSON.stringify(sites));
       setSavedSites(sites);
       // Dispatch custom event for same-window sync with sidebar
       window.dispatchEvent(new CustomEvent(QUICK_SITES_CHANGE_EVENT, { detail: sites }));
@@ -99,7 +100,7 @@ const QuickSitesPage: React.FC<QuickSitesPageProps> = ({ language, onNavigate })
     };
 
     const handleStorageChange = (e: StorageEvent) => {
-      if (e.key === QUICK_SITES_STORAGE_KEY && e.newValue) {
+      if (e.key === profileStorage.key(QUICK_SITES_STORAGE_KEY) && e.newValue) {
         try {
           setSavedSites(JSON.parse(e.newValue));
         } catch {
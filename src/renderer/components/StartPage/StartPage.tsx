@@ -1,5 +1,5 @@
 import React, { useState, useEffect, KeyboardEvent, useMemo } from 'react';
-import { Settings } from '../../types';
+import { Settings, Transition } from '../../types';
 import { Language, translations, type Translations } from '../../i18n';
 import {
   SunIcon,
@@ -16,7 +16,7 @@ import '../../styles/pages/start-page.css';
 interface StartPageProps {
   settings: Settings;
   language?: Language;
-  onNavigate: (url: string) => void;
+  onNavigate: (url: string, transition?: Transition) => void;
   recentSites?: Array<{ url: string; title: string; favicon?: string }>;
   hiddenSites?: string[]; // URLs скрытых сайтов
   renamedSites?: Record<string, string>; // URL -> новое имя
@@ -214,7 +214,7 @@ useEffect(() => {
 
   const handleSearch = (e: KeyboardEvent<HTMLInputElement>) => {
     if (e.key === 'Enter' && searchValue.trim()) {
-      onNavigate(searchValue);
+      onNavigate(searchValue, 'typed');
     }
   };
 
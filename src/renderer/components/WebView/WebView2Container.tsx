@@ -82,6 +82,20 @@ const WebView2Container: React.FC<WebView2ContainerProps> = ({
     }, 16); // ~60fps
   }, [tab.id]);
 
+  // Посещение текущего адреса. Способ перехода (ввод в адресной строке)
+  // относится только к одному посещению, поэтому сразу сбрасываем его
+  const recordVisit = () => {
+    onAddHistory({
+      url: tab.url,
+      title: tab.title || tab.url,
+      favicon: getFaviconUrl(tab.url),
+      transition: tab.transition ?? 'link',
+    });
+    if (tab.transition) {
+      onUpdate({ transition: undefined });
+    }
+  };
+
   // Создание WebView при монтировании - оптимизировано для быстрой загрузки
   useEffect(() => {
     if (!tab.url || isCreating) return;
@@ -176,11 +190,7 @@ const WebView2Container: React.FC<WebView2ContainerProps> = ({
         }, 50);
 
         // Добавляем в историю с кэшированным favicon
-        onAddHistory({
-          url: tab.url,
-          title: tab.title || tab.url,
-          favicon: getFaviconUrl(tab.url),
-        });
+        recordVisit();
       } catch (error) {
         console.error('Failed to create webview:', error);
         onUpdate({ isLoading: false });
@@ -235,11 +245,7 @@ const WebView2Container: React.FC<WebView2ContainerProps> = ({
         }, 30);
 
         // Добавляем в историю с кэшированным favicon
-        onAddHistory({
-          url: tab.url,
-          title: tab.title || tab.url,
-          favicon: getFaviconUrl(tab.url),
-        });
+        recordVisit();
       } catch (error) {
         console.error('Navigation failed:', error);
         onUpdate({ isLoading: false });
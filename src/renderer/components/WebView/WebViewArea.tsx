@@ -7,7 +7,7 @@ import QuickSitesPage from '../QuickSites/QuickSitesPage';
 import StartPage from '../StartPage/StartPage';
 import { SnowflakeIcon } from '../ZenSidebar/icons';
 import { INTERNAL_URLS } from '../../constants';
-import { Settings, Workspace, Tab, HistoryEntry } from '../../types';
+import { Settings, Workspace, Tab, HistoryEntry, Transition } from '../../types';
 
 interface WebViewAreaProps {
   workspaces: Workspace[];
@@ -21,7 +21,7 @@ interface WebViewAreaProps {
   webviewRefs: React.MutableRefObject<Map<string, HTMLWebViewElement>>;
   createNewTab: (url?: string) => void;
   unfreezeTab: (tabId: string) => void;
-  navigate: (url: string) => void;
+  navigate: (url: string, transition?: Transition) => void;
   clearHistory: () => void;
   updateSettings: (settings: Partial<Settings>) => void;
   
@@ -1,12 +1,13 @@
 import React from 'react';
 import { SettingsIcon, ProfileIcon, HistoryIcon, DownloadsIcon } from '../icons';
 import { useTranslation } from '../../../hooks/useTranslation';
+import { Language } from '../../../types';
 
 interface BottomToolbarProps {
   onShowSettings?: () => void;
   onShowHistory: () => void;
   onShowDownloads: () => void;
-  language: 'ru' | 'en';
+  language: Language;
 }
 
 export const BottomToolbar: React.FC<BottomToolbarProps> = ({
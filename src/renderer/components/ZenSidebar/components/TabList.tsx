@@ -1,5 +1,5 @@
 import React, { useState, useRef, useCallback } from 'react';
-import { Tab } from '../../../types';
+import { Tab, Language } from '../../../types';
 import { useTranslation } from '../../../hooks/useTranslation';
 import { 
   PlusIcon, 
@@ -24,7 +24,7 @@ interface TabListProps {
   onTabSelect: (id: string) => void;
   onTabClose: (id: string) => void;
   onNewTab: () => void;
-  language: 'ru' | 'en';
+  language: Language;
 }
 
 const getInternalIcon = (url?: string) => {
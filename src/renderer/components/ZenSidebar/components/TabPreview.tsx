@@ -1,5 +1,5 @@
 import React from 'react';
-import { Tab } from '../../../types';
+import { Tab, Language } from '../../../types';
 import { useTranslation } from '../../../hooks/useTranslation';
 import { GlobeIcon, HistoryTabIcon, DownloadsTabIcon, SettingsTabIcon, HomeTabIcon, QuickSitesTabIcon, SnowflakeIcon } from '../icons';
 
@@ -7,7 +7,7 @@ interface TabPreviewProps {
   tab: Tab;
   position: { x: number; y: number };
   sidebarPosition: 'left' | 'right';
-  language: 'ru' | 'en';
+  language: Language;
 }
 
 const getInternalIcon = (url?: string) => {
@@ -1,6 +1,6 @@
 import React, { RefObject, useState } from 'react';
 import { createPortal } from 'react-dom';
-import { Workspace } from '../../../types';
+import { Workspace, Language } from '../../../types';
 import { ContextMenuPosition } from '../types';
 import { 
   WORKSPACE_ICONS, 
@@ -54,7 +54,7 @@ interface WorkspaceListProps {
   onOpenContextMenu: (e: React.MouseEvent, workspaceId: string) => void;
   onOpenIconPicker: () => void;
   onCloseContextMenu: () => void;
-  language: 'ru' | 'en';
+  language: Language;
 }
 
 export const WorkspaceList: React.FC<WorkspaceListProps> = ({
@@ -1,6 +1,7 @@
 import { useState, useRef, useEffect, useCallback, RefObject } from 'react';
 import { ContextMenuPosition, QuickSite } from './types';
 import { MIN_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH, DEFAULT_QUICK_SITES } from './constants';
+import { profileStorage } from '../../utils/profileStorage';
 
 const QUICK_SITES_STORAGE_KEY = 'axion-quick-sites';
 const QUICK_SITES_CHANGE_EVENT = 'quick-sites-changed';
@@ -11,7 +12,7 @@ const QUICK_SITES_CHANGE_EVENT = 'quick-sites-changed';
 export function useQuickSites() {
   const [sites, setSites] = useState<QuickSite[]>(() => {
     try {
-      const stored = localStorage.getItem(QUICK_SITES_STORAGE_KEY);
+      const stored = profileStorage.getItem(QUICK_SITES_STORAGE_KEY);
       if (stored) {
         return JSON.parse(stored);
       }
@@ -26,7 +27,7 @@ export function useQuickSites() {
   // Persist to localStorage and dispatch custom event
   const saveSites = useCallback((newSites: QuickSite[]) => {
     try {
-      localStorage.setItem(QUICK_SITES_STORAGE_KEY, JSON.stringify(newSites));
+      profileStorage.setItem(QUICK_SITES_STORAGE_KEY, JSON.stringify(newSites));
       setSites(newSites);
       // Dispatch custom event for same-window sync
       window.dispatchEvent(new CustomEvent(QUICK_SITES_CHANGE_EVENT, { detail: newSites }));
@@ -42,7 +43,7 @@ export function useQuickSites() {
     };
 
     const handleStorageChange = (e: StorageEvent) => {
-      if (e.key === QUICK_SITES_STORAGE_KEY && e.newValue) {
+      if (e.key === profileStorage.key(QUICK_SITES_STORAGE_KEY) && e.newValue) {
         try {
           setSites(JSON.parse(e.newValue));
         } catch {
@@ -91,5 +91,8 @@ export {
   FolderIcon,
   FolderOpenIcon,
   PauseIcon,
+  PlayIcon,
+  ArrowUpIcon,
+  ChevronsUpIcon,
   CheckIcon,
 } from './miscIcons';
@@ -0,0 +1,94 @@
+import React, { useState, useEffect } from 'react';
+import { SettingsTabProps } from './types';
+import { Toggle, SettingItem } from './SettingsComponents';
+import { DownloadRule } from '../../types';
+import { FolderIcon, PlusIcon, TrashIcon } from '../ZenSidebar/icons';
+
+// Правило сохраняется, когда заполнены и тип файла, и папка
+const isCompleteRule = (rule: DownloadRule) =>
+  rule.match.split(',').some(item => item.trim() !== '') && rule.folder !== '';
+
+export const DownloadLocationSettings: React.FC<SettingsTabProps> = ({ settings, onUpdate, t }) => {
+  const [defaultFolder, setDefaultFolder] = useState('');
+  // Черновик правил: незаполненные правила живут только здесь
+  const [rules, setRules] = useState<DownloadRule[]>(settings.downloadRules);
+
+  useEffect(() => {
+    window.electronAPI.getDownloadsFolder()
+      .then(setDefaultFolder)
+      .catch(() => setDefaultFolder(''));
+  }, [settings.downloadPath]);
+
+  const pickDownloadPath = async () => {
+    const folder = await window.electronAPI.pickDownloadFolder(defaultFolder);
+    if (folder) onUpdate({ downloadPath: folder });
+  };
+
+  const updateRules = (next: DownloadRule[]) => {
+    setRules(next);
+    onUpdate({ downloadRules: next.filter(isCompleteRule) });
+  };
+
+  const updateRule = (index: number, patch: Partial<DownloadRule>) =>
+    updateRules(rules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)));
+
+  const pickRuleFolder = async (index: number) => {
+    const folder = await window.electronAPI.pickDownloadFolder(rules[index].folder || defaultFolder);
+    if (folder) updateRule(index, { folder });
+  };
+
+  return (
+    <>
+      <SettingItem label={t.settings.downloadFolder} description={defaultFolder || t.settings.downloadFolderDesc}>
+        <div className="download-folder-actions">
+          {settings.downloadPath && (
+            <button className="download-folder-button" onClick={() => onUpdate({ downloadPath: '' })}>
+              {t.settings.resetDownloadFolder}
+            </button>
+          )}
+          <button className="download-folder-button" onClick={pickDownloadPath}>
+            <FolderIcon size={14} /> {t.settings.changeDownloadFolder}
+          </button>
+        </div>
+      </SettingItem>
+
+      <SettingItem label={t.settings.askWhereToSave} description={t.settings.askWhereToSaveDesc}>
+        <Toggle checked={settings.askWhereToSave} onChange={(v) => onUpdate({ askWhereToSave: v })} />
+      </SettingItem>
+
+      <SettingItem label={t.settings.downloadRules} description={t.settings.downloadRulesDesc} vertical>
+        <div className="download-rules">
+          {rules.map((rule, index) => (
+            <div key={index} className="download-rule">
+              <input
+                type="text"
+                className="download-rule-match"
+                value={rule.match}
+                placeholder={t.settings.downloadRuleMatchPlaceholder}
+                onChange={(e) => updateRule(index, { match: e.target.value })}
+              />
+              <button
+                className="download-rule-folder"
+                title={rule.folder || t.settings.changeDownloadFolder}
+                onClick={() => pickRuleFolder(index)}
+              >
+                <FolderIcon size={14} />
+                <span>{rule.folder || t.settings.changeDownloadFolder}</span>
+              </button>
+              <button
+                className="download-rule-remove"
+                title={t.settings.removeDownloadRule}
+                onClick={() => updateRules(rules.filter((_, i) => i !== index))}
+              >
+                <TrashIcon />
+              </button>
+            </div>
+          ))}
+          <button className="download-folder-button" onClick={() => setRules([...rules, { match: '', folder: '' }])}>
+            <PlusIcon /> {t.settings.addDownloadRule}
+          </button>
+        </div>
+      </SettingItem>
+    </>
+  );
+};
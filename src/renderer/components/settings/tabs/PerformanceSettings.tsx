@@ -1,6 +1,15 @@
 import React from 'react';
 import { SettingsTabProps } from '../types';
-import { Toggle, SettingItem } from '../SettingsComponents';
+import { Toggle, Select, SettingItem } from '../SettingsComponents';
+import { DownloadLocationSettings } from '../DownloadLocationSettings';
+import { SPEED_LIMIT_PRESETS, formatSpeedLimit } from '../../Downloads/speedLimits';
+
+// Порог размера загрузки, после которого нужно подтверждение, байт
+const MB = 1024 * 1024;
+const CONFIRM_SIZE_PRESETS = [100 * MB, 500 * MB, 1024 * MB, 2048 * MB, 4096 * MB, 10240 * MB];
+
+const formatConfirmSize = (bytes: number) =>
+  bytes >= 1024 * MB ? `${bytes / (1024 * MB)} GB` : `${bytes / MB} MB`;
 
 export const PerformanceSettings: React.FC<SettingsTabProps> = ({ settings, onUpdate, t }) => (
   <>
@@ -38,5 +47,54 @@ export const PerformanceSettings: React.FC<SettingsTabProps> = ({ settings, onUp
         </SettingItem>
       )}
     </div>
+
+    <div className="settings-page-section">
+      <h2>{t.settings.downloadsSection}</h2>
+      <DownloadLocationSettings settings={settings} onUpdate={onUpdate} t={t} />
+
+      <SettingItem label={t.settings.maxConcurrentDownloads} description={`${settings.maxConcurrentDownloads} ${t.settings.maxConcurrentDownloadsDesc}`}>
+        <input
+          type="range"
+          min="1"
+          max="10"
+          step="1"
+          value={settings.maxConcurrentDownloads}
+          onChange={(e) => onUpdate({ maxConcurrentDownloads: parseInt(e.target.value) })}
+        />
+      </SettingItem>
+
+      <SettingItem label={t.settings.downloadConnections} description={`${settings.downloadConnections} ${t.settings.downloadConnectionsDesc}`}>
+        <input
+          type="range"
+          min="1"
+          max="16"
+          step="1"
+          value={settings.downloadConnections}
+          onChange={(e) => onUpdate({ downloadConnections: parseInt(e.target.value) })}
+        />
+      </SettingItem>
+
+      <SettingItem label={t.settings.downloadSpeedLimit} description={t.settings.downloadSpeedLimitDesc}>
+        <Select
+          value={String(settings.downloadSpeedLimit)}
+          options={[
+            { value: '0', label: t.common.unlimited },
+            ...SPEED_LIMIT_PRESETS.map(limit => ({ value: String(limit), label: formatSpeedLimit(limit) })),
+          ]}
+          onChange={(v) => onUpdate({ downloadSpeedLimit: parseInt(v) })}
+        />
+      </SettingItem>
+
+      <SettingItem label={t.settings.downloadConfirmSize} description={t.settings.downloadConfirmSizeDesc}>
+        <Select
+          value={String(settings.downloadConfirmSize)}
+          options={[
+            { value: '0', label: t.settings.downloadConfirmSizeNever },
+            ...CONFIRM_SIZE_PRESETS.map(size => ({ value: String(size), label: formatConfirmSize(size) })),
+          ]}
+          onChange={(v) => onUpdate({ downloadConfirmSize: parseInt(v) })}
+        />
+      </SettingItem>
+    </div>
   </>
 );
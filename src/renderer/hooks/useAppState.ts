@@ -1,5 +1,6 @@
 import { useState, useRef, useMemo } from 'react';
 import { Settings, defaultSettings } from '../types';
+import { profileStorage } from '../utils/profileStorage';
 
 export const useAppState = () => {
   // Базовые состояния
@@ -15,11 +16,11 @@ export const useAppState = () => {
   
   // Состояния для StartPage сайтов
   const [hiddenSites, setHiddenSites] = useState<string[]>(() => {
-    const saved = localStorage.getItem('hiddenSites');
+    const saved = profileStorage.getItem('hiddenSites');
     return saved ? JSON.parse(saved) : [];
   });
   const [renamedSites, setRenamedSites] = useState<Record<string, string>>(() => {
-    const saved = localStorage.getItem('renamedSites');
+    const saved = profileStorage.getItem('renamedSites');
     return saved ? JSON.parse(saved) : {};
   });
   
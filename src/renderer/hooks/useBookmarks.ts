@@ -1,6 +1,7 @@
 import { useState, useCallback } from 'react';
 import { v4 as uuidv4 } from 'uuid';
 import { Bookmark, Workspace, HistoryEntry } from '../types';
+import { mergeImportedBookmarks } from '../utils/bookmarks';
 
 interface UseBookmarksOptions {
   workspaces: Workspace[];
@@ -57,9 +58,7 @@ export const useBookmarks = ({ workspaces, activeWorkspaceId }: UseBookmarksOpti
     const result = await window.electronAPI.importFromBrowser(browser);
     if (result) {
       // Объединяем закладки
-      const mergedBookmarks = [...bookmarks, ...result.bookmarks.filter(
-        imported => !bookmarks.some(b => b.url === imported.url)
-      )];
+      const mergedBookmarks = mergeImportedBookmarks(bookmarks, result.bookmarks);
       setBookmarks(mergedBookmarks);
       window.electronAPI.setBookmarks(mergedBookmarks);
       
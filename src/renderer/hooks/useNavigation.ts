@@ -1,5 +1,5 @@
 import { useCallback, useRef, useEffect } from 'react';
-import { Tab, Workspace, Settings } from '../types';
+import { Tab, Workspace, Settings, Transition } from '../types';
 import { normalizeUrl, getInternalPageTitle } from '../utils/url';
 import { INTERNAL_URLS } from '../constants';
 import { v4 as uuidv4 } from 'uuid';
@@ -27,7 +27,7 @@ export const useNavigation = ({
     activeTabIdRef.current = activeTabId; 
   }, [activeTabId]);
 
-  const navigate = useCallback((url: string) => {
+  const navigate = useCallback((url: string, transition: Transition = 'link') => {
     const currentActiveId = activeTabIdRef.current;
     if (!currentActiveId) return;
     
@@ -53,8 +53,21 @@ export const useNavigation = ({
       return;
     }
 
+    // Ключевые слова закладок: "gh rust" → URL закладки с подставленным запросом
+    const firstWord = finalUrl.split(/\s+/)[0];
+    if (!finalUrl.includes('://') && !firstWord.includes('.')) {
+      const input = finalUrl;
+      window.electronAPI.resolveKeyword(input)
+        .catch(() => null)
+        .then(resolved => {
+          const target = resolved ?? normalizeUrl(input, settings.searchEngine);
+          updateTab(currentActiveId, { url: target, isLoading: true, transition });
+        });
+      return;
+    }
+
     finalUrl = normalizeUrl(finalUrl, settings.searchEngine);
-    updateTab(currentActiveId, { url: finalUrl, isLoading: true });
+    updateTab(currentActiveId, { url: finalUrl, isLoading: true, transition });
   }, [settings.searchEngine, updateTab]);
 
   const goBack = useCallback(async () => {
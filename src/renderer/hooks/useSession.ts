@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
 import { v4 as uuidv4 } from 'uuid';
 import { Tab, Workspace, Settings, Bookmark, HistoryEntry, defaultSettings } from '../types';
+import { profileStorage } from '../utils/profileStorage';
 
 interface UseSessionOptions {
   workspaces: Workspace[];
@@ -54,7 +55,7 @@ export const useSession = ({
       await loadData();
       
       // Восстанавливаем ширину сайдбара из localStorage
-      const savedWidth = localStorage.getItem('sidebarWidth');
+      const savedWidth = profileStorage.getItem('sidebarWidth');
       if (savedWidth) {
         setSidebarWidth(parseInt(savedWidth, 10));
       }
@@ -1,20 +1,21 @@
 import { useState, useCallback } from 'react';
+import { profileStorage } from '../utils/profileStorage';
 
 export const useStartPageData = () => {
   const [hiddenSites, setHiddenSites] = useState<string[]>(() => {
-    const saved = localStorage.getItem('hiddenSites');
+    const saved = profileStorage.getItem('hiddenSites');
     return saved ? JSON.parse(saved) : [];
   });
 
   const [renamedSites, setRenamedSites] = useState<Record<string, string>>(() => {
-    const saved = localStorage.getItem('renamedSites');
+    const saved = profileStorage.getItem('renamedSites');
     return saved ? JSON.parse(saved) : {};
   });
 
   const handleHideSite = useCallback((url: string) => {
     setHiddenSites(prev => {
       const updated = [...prev, url];
-      localStorage.setItem('hiddenSites', JSON.stringify(updated));
+      profileStorage.setItem('hiddenSites', JSON.stringify(updated));
       return updated;
     });
   }, []);
@@ -23,14 +24,14 @@ export const useStartPageData = () => {
     // Удаление = скрытие + удаление из истории (история обрабатывается отдельно, но скрытие здесь)
     setHiddenSites(prev => {
       const updated = [...prev, url];
-      localStorage.setItem('hiddenSites', JSON.stringify(updated));
+      profileStorage.setItem('hiddenSites', JSON.stringify(updated));
       return updated;
     });
     // Также удаляем переименование если было
     setRenamedSites(prev => {
       const updated = { ...prev };
       delete updated[url];
-      localStorage.setItem('renamedSites', JSON.stringify(updated));
+      profileStorage.setItem('renamedSites', JSON.stringify(updated));
       return updated;
     });
   }, []);
@@ -38,7 +39,7 @@ export const useStartPageData = () => {
   const handleRenameSite = useCallback((url: string, newName: string) => {
     setRenamedSites(prev => {
       const updated = { ...prev, [url]: newName };
-      localStorage.setItem('renamedSites', JSON.stringify(updated));
+      profileStorage.setItem('renamedSites', JSON.stringify(updated));
       return updated;
     });
   }, []);
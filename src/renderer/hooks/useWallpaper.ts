@@ -1,4 +1,5 @@
 import { useState, useEffect } from 'react';
+import { profileStorage } from '../utils/profileStorage';
 
 const WALLPAPERS = [
   'walpaper1.jpg',
@@ -24,14 +25,14 @@ export const useWallpaper = () => {
   const changeWallpaper = () => {
     const wallpaper = getRandomWallpaper();
     setCurrentWallpaper(wallpaper);
-    localStorage.setItem('lastWallpaper', wallpaper);
-    localStorage.setItem('lastWallpaperChange', Date.now().toString());
+    profileStorage.setItem('lastWallpaper', wallpaper);
+    profileStorage.setItem('lastWallpaperChange', Date.now().toString());
   };
 
   useEffect(() => {
     // При первой загрузке проверяем, нужно ли менять обои
-    const lastWallpaper = localStorage.getItem('lastWallpaper');
-    const lastChange = localStorage.getItem('lastWallpaperChange');
+    const lastWallpaper = profileStorage.getItem('lastWallpaper');
+    const lastChange = profileStorage.getItem('lastWallpaperChange');
     const now = Date.now();
 
     if (!lastWallpaper || !lastChange || now - parseInt(lastChange) > CHANGE_INTERVAL) {
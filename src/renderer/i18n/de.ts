@@ -119,6 +119,27 @@ export const de: Translations = {
     tabSuspensionDesc: 'Inaktive Tabs aus dem Speicher entladen',
     suspensionTimeout: 'Suspendierungs-Timeout',
     suspensionTimeoutDesc: 'Minuten',
+    downloadsSection: 'Downloads',
+    maxConcurrentDownloads: 'Gleichzeitige Downloads',
+    maxConcurrentDownloadsDesc: 'Downloads gleichzeitig, der Rest wartet in der Warteschlange',
+    downloadConnections: 'Verbindungen pro Download',
+    downloadConnectionsDesc: 'parallele Verbindungen für große Dateien, sofern der Server sie unterstützt',
+    downloadSpeedLimit: 'Download-Geschwindigkeitslimit',
+    downloadSpeedLimitDesc: 'gilt für alle Downloads; ein Download kann ein eigenes Limit haben',
+    downloadConfirmSize: 'Vor großen Downloads fragen',
+    downloadConfirmSizeDesc: 'größere Downloads starten erst nach Bestätigung',
+    downloadConfirmSizeNever: 'Nie',
+    downloadFolder: 'Download-Ordner',
+    downloadFolderDesc: 'Wo heruntergeladene Dateien gespeichert werden',
+    changeDownloadFolder: 'Auswählen…',
+    resetDownloadFolder: 'Zurücksetzen',
+    askWhereToSave: 'Für jede Datei nach dem Speicherort fragen',
+    askWhereToSaveDesc: 'Vor jedem Download einen Speichern-Dialog anzeigen',
+    downloadRules: 'Ordner nach Dateityp',
+    downloadRulesDesc: 'Passende Dateien in eigenen Ordnern speichern; die erste passende Regel gilt',
+    downloadRuleMatchPlaceholder: 'application/pdf, image/*, .zip',
+    addDownloadRule: 'Regel hinzufügen',
+    removeDownloadRule: 'Regel entfernen',
     // Advanced
     interface: 'Oberfläche',
     bookmarksBar: 'Lesezeichenleiste',
@@ -171,6 +192,25 @@ export const de: Translations = {
     completed: 'Abgeschlossen',
     cancelled: 'Abgebrochen',
     interrupted: 'Unterbrochen',
+    paused: 'Pausiert',
+    resumeAll: 'Alle fortsetzen',
+    downloadsInterrupted: 'Einige Downloads wurden beim Schließen des Browsers unterbrochen. Sie können sie auf der Download-Seite fortsetzen.',
+    queued: 'In der Warteschlange',
+    retrying: 'Wiederholungsversuch',
+    verificationFailed: 'Prüfsumme stimmt nicht überein',
+    timeLeft: 'Verbleibend',
+    elapsed: 'Vergangen',
+    dangerousFile: 'Diese Datei kann Ihrem Computer schaden',
+    keepFile: 'Behalten',
+    discardFile: 'Verwerfen',
+    largeDownload: 'Große Datei',
+    startDownload: 'Download starten',
+    insufficientSpace: 'Nicht genügend Speicherplatz',
+    moveUp: 'Nach oben',
+    downloadNext: 'Als Nächstes laden',
+    speedLimit: 'Geschwindigkeitslimit',
+    speedLimitDefault: 'Standardlimit',
+    unlimited: 'Unbegrenzt',
     cancel: 'Abbrechen',
     open: 'Öffnen',
     showInFolder: 'Im Ordner anzeigen',
@@ -119,6 +119,27 @@ export const en: Translations = {
     tabSuspensionDesc: 'Unload inactive tabs from memory',
     suspensionTimeout: 'Suspension Timeout',
     suspensionTimeoutDesc: 'minutes',
+    downloadsSection: 'Downloads',
+    maxConcurrentDownloads: 'Simultaneous downloads',
+    maxConcurrentDownloadsDesc: 'downloads at a time, the rest wait in the queue',
+    downloadConnections: 'Connections per download',
+    downloadConnectionsDesc: 'parallel connections for large files on servers that support it',
+    downloadSpeedLimit: 'Download speed limit',
+    downloadSpeedLimitDesc: 'shared by all downloads; a download can have its own limit',
+    downloadConfirmSize: 'Ask before large downloads',
+    downloadConfirmSizeDesc: 'downloads larger than this start only after you confirm',
+    downloadConfirmSizeNever: 'Never',
+    downloadFolder: 'Download folder',
+    downloadFolderDesc: 'Where downloaded files are saved',
+    changeDownloadFolder: 'Choose…',
+    resetDownloadFolder: 'Reset',
+    askWhereToSave: 'Ask where to save each file',
+    askWhereToSaveDesc: 'Show a save dialog before every download',
+    downloadRules: 'Folders by file type',
+    downloadRulesDesc: 'Save matching files to their own folders; the first matching rule wins',
+    downloadRuleMatchPlaceholder: 'application/pdf, image/*, .zip',
+    addDownloadRule: 'Add rule',
+    removeDownloadRule: 'Remove rule',
     // Advanced
     interface: 'Interface',
     bookmarksBar: 'Bookmarks Bar',
@@ -171,6 +192,25 @@ export const en: Translations = {
     completed: 'Completed',
     cancelled: 'Cancelled',
     interrupted: 'Interrupted',
+    paused: 'Paused',
+    resumeAll: 'Resume All',
+    downloadsInterrupted: 'Some downloads were interrupted when the browser closed. You can resume them on the Downloads page.',
+    queued: 'Queued',
+    retrying: 'Retry attempt',
+    verificationFailed: 'Checksum mismatch',
+    timeLeft: 'Time left',
+    elapsed: 'Elapsed',
+    dangerousFile: 'This file may harm your computer',
+    keepFile: 'Keep',
+    discardFile: 'Discard',
+    largeDownload: 'Large file',
+    startDownload: 'Start download',
+    insufficientSpace: 'Not enough disk space',
+    moveUp: 'Move up',
+    downloadNext: 'Download next',
+    speedLimit: 'Speed limit',
+    speedLimitDefault: 'Default limit',
+    unlimited: 'Unlimited',
     cancel: 'Cancel',
     open: 'Open',
     showInFolder: 'Show in Folder',
@@ -119,6 +119,27 @@ export const es: Translations = {
     tabSuspensionDesc: 'Descargar pestañas inactivas de la memoria',
     suspensionTimeout: 'Tiempo de suspensión',
     suspensionTimeoutDesc: 'minutos',
+    downloadsSection: 'Descargas',
+    maxConcurrentDownloads: 'Descargas simultáneas',
+    maxConcurrentDownloadsDesc: 'descargas a la vez, el resto espera en la cola',
+    downloadConnections: 'Conexiones por descarga',
+    downloadConnectionsDesc: 'conexiones paralelas para archivos grandes si el servidor lo permite',
+    downloadSpeedLimit: 'Límite de velocidad de descarga',
+    downloadSpeedLimitDesc: 'compartido por todas las descargas; una descarga puede tener su propio límite',
+    downloadConfirmSize: 'Preguntar antes de descargas grandes',
+    downloadConfirmSizeDesc: 'las descargas más grandes empiezan solo tras confirmarlas',
+    downloadConfirmSizeNever: 'Nunca',
+    downloadFolder: 'Carpeta de descargas',
+    downloadFolderDesc: 'Dónde se guardan los archivos descargados',
+    changeDownloadFolder: 'Elegir…',
+    resetDownloadFolder: 'Restablecer',
+    askWhereToSave: 'Preguntar dónde guardar cada archivo',
+    askWhereToSaveDesc: 'Mostrar un diálogo de guardado antes de cada descarga',
+    downloadRules: 'Carpetas por tipo de archivo',
+    downloadRulesDesc: 'Guardar los archivos coincidentes en sus propias carpetas; se aplica la primera regla que coincida',
+    downloadRuleMatchPlaceholder: 'application/pdf, image/*, .zip',
+    addDownloadRule: 'Añadir regla',
+    removeDownloadRule: 'Eliminar regla',
     // Advanced
     interface: 'Interfaz',
     bookmarksBar: 'Barra de marcadores',
@@ -171,6 +192,25 @@ export const es: Translations = {
     completed: 'Completado',
     cancelled: 'Cancelado',
     interrupted: 'Interrumpido',
+    paused: 'En pausa',
+    resumeAll: 'Reanudar todo',
+    downloadsInterrupted: 'Algunas descargas se interrumpieron al cerrar el navegador. Puedes reanudarlas en la página de descargas.',
+    queued: 'En cola',
+    retrying: 'Reintento',
+    verificationFailed: 'La suma de verificación no coincide',
+    timeLeft: 'Tiempo restante',
+    elapsed: 'Transcurrido',
+    dangerousFile: 'Este archivo puede dañar tu equipo',
+    keepFile: 'Conservar',
+    discardFile: 'Descartar',
+    largeDownload: 'Archivo grande',
+    startDownload: 'Iniciar descarga',
+    insufficientSpace: 'No hay espacio suficiente en el disco',
+    moveUp: 'Subir',
+    downloadNext: 'Descargar a continuación',
+    speedLimit: 'Límite de velocidad',
+    speedLimitDefault: 'Límite general',
+    unlimited: 'Sin límite',
     cancel: 'Cancelar',
     open: 'Abrir',
     showInFolder: 'Mostrar en carpeta',
@@ -119,6 +119,27 @@ export const fr: Translations = {
     tabSuspensionDesc: 'Décharger les onglets inactifs de la mémoire',
     suspensionTimeout: 'Délai de suspension',
     suspensionTimeoutDesc: 'minutes',
+    downloadsSection: 'Téléchargements',
+    maxConcurrentDownloads: 'Téléchargements simultanés',
+    maxConcurrentDownloadsDesc: 'téléchargements à la fois, les autres attendent dans la file',
+    downloadConnections: 'Connexions par téléchargement',
+    downloadConnectionsDesc: 'connexions parallèles pour les gros fichiers si le serveur le permet',
+    downloadSpeedLimit: 'Limite de vitesse des téléchargements',
+    downloadSpeedLimitDesc: 'partagée par tous les téléchargements ; un téléchargement peut avoir sa propre limite',
+    downloadConfirmSize: 'Demander avant les gros téléchargements',
+    downloadConfirmSizeDesc: 'les téléchargements plus volumineux ne démarrent qu\'après confirmation',
+    downloadConfirmSizeNever: 'Jamais',
+    downloadFolder: 'Dossier de téléchargement',
+    downloadFolderDesc: 'Où les fichiers téléchargés sont enregistrés',
+    changeDownloadFolder: 'Choisir…',
+    resetDownloadFolder: 'Réinitialiser',
+    askWhereToSave: 'Demander où enregistrer chaque fichier',
+    askWhereToSaveDesc: 'Afficher une boîte de dialogue d\'enregistrement avant chaque téléchargement',
+    downloadRules: 'Dossiers par type de fichier',
+    downloadRulesDesc: 'Enregistrer les fichiers correspondants dans leurs propres dossiers ; la première règle correspondante s\'applique',
+    downloadRuleMatchPlaceholder: 'application/pdf, image/*, .zip',
+    addDownloadRule: 'Ajouter une règle',
+    removeDownloadRule: 'Supprimer la règle',
     // Advanced
     interface: 'Interface',
     bookmarksBar: 'Barre de favoris',
@@ -171,6 +192,25 @@ export const fr: Translations = {
     completed: 'Terminé',
     cancelled: 'Annulé',
     interrupted: 'Interrompu',
+    paused: 'En pause',
+    resumeAll: 'Tout reprendre',
+    downloadsInterrupted: 'Certains téléchargements ont été interrompus à la fermeture du navigateur. Vous pouvez les reprendre sur la page Téléchargements.',
+    queued: 'En attente',
+    retrying: 'Nouvelle tentative',
+    verificationFailed: 'Somme de contrôle incorrecte',
+    timeLeft: 'Temps restant',
+    elapsed: 'Écoulé',
+    dangerousFile: 'Ce fichier peut endommager votre ordinateur',
+    keepFile: 'Conserver',
+    discardFile: 'Supprimer',
+    largeDownload: 'Fichier volumineux',
+    startDownload: 'Démarrer le téléchargement',
+    insufficientSpace: 'Espace disque insuffisant',
+    moveUp: 'Monter',
+    downloadNext: 'Télécharger ensuite',
+    speedLimit: 'Limite de vitesse',
+    speedLimitDefault: 'Limite générale',
+    unlimited: 'Illimité',
     cancel: 'Annuler',
     open: 'Ouvrir',
     showInFolder: 'Afficher dans le dossier',
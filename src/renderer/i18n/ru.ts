@@ -119,6 +119,27 @@ export const ru: Translations = {
     tabSuspensionDesc: 'Выгружать неактивные вкладки из памяти',
     suspensionTimeout: 'Таймаут заморозки',
     suspensionTimeoutDesc: 'минут',
+    downloadsSection: 'Загрузки',
+    maxConcurrentDownloads: 'Одновременные загрузки',
+    maxConcurrentDownloadsDesc: 'загрузок одновременно, остальные ждут в очереди',
+    downloadConnections: 'Соединений на загрузку',
+    downloadConnectionsDesc: 'параллельных соединений для больших файлов, если сервер их поддерживает',
+    downloadSpeedLimit: 'Ограничение скорости загрузок',
+    downloadSpeedLimitDesc: 'общее для всех загрузок; у загрузки может быть свой лимит',
+    downloadConfirmSize: 'Спрашивать перед большими загрузками',
+    downloadConfirmSizeDesc: 'загрузки больше этого размера начнутся только после подтверждения',
+    downloadConfirmSizeNever: 'Никогда',
+    downloadFolder: 'Папка загрузок',
+    downloadFolderDesc: 'Куда сохраняются скачанные файлы',
+    changeDownloadFolder: 'Выбрать…',
+    resetDownloadFolder: 'Сбросить',
+    askWhereToSave: 'Спрашивать, куда сохранить файл',
+    askWhereToSaveDesc: 'Показывать диалог сохранения перед каждой загрузкой',
+    downloadRules: 'Папки по типу файла',
+    downloadRulesDesc: 'Подходящие файлы сохраняются в свои папки; срабатывает первое подходящее правило',
+    downloadRuleMatchPlaceholder: 'application/pdf, image/*, .zip',
+    addDownloadRule: 'Добавить правило',
+    removeDownloadRule: 'Удалить правило',
     // Advanced
     interface: 'Интерфейс',
     bookmarksBar: 'Панель закладок',
@@ -171,6 +192,25 @@ export const ru: Translations = {
     completed: 'Завершено',
     cancelled: 'Отменено',
     interrupted: 'Прервано',
+    paused: 'Приостановлено',
+    resumeAll: 'Продолжить все',
+    downloadsInterrupted: 'Некоторые загрузки прервались при закрытии браузера. Их можно продолжить на странице загрузок.',
+    queued: 'В очереди',
+    retrying: 'Повторная попытка',
+    verificationFailed: 'Контрольная сумма не совпадает',
+    timeLeft: 'Осталось',
+    elapsed: 'Прошло',
+    dangerousFile: 'Этот файл может навредить компьютеру',
+    keepFile: 'Сохранить',
+    discardFile: 'Удалить',
+    largeDownload: 'Большой файл',
+    startDownload: 'Начать загрузку',
+    insufficientSpace: 'Недостаточно места на диске',
+    moveUp: 'Выше в очереди',
+    downloadNext: 'Загрузить следующей',
+    speedLimit: 'Ограничение скорости',
+    speedLimitDefault: 'Общий лимит',
+    unlimited: 'Без ограничений',
     cancel: 'Отменить',
     open: 'Открыть',
     showInFolder: 'Показать в папке',
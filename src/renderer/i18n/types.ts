@@ -120,6 +120,27 @@ export interface Translations {
     tabSuspensionDesc: string;
     suspensionTimeout: string;
     suspensionTimeoutDesc: string;
+    downloadsSection: string;
+    maxConcurrentDownloads: string;
+    maxConcurrentDownloadsDesc: string;
+    downloadConnections: string;
+    downloadConnectionsDesc: string;
+    downloadSpeedLimit: string;
+    downloadSpeedLimitDesc: string;
+    downloadConfirmSize: string;
+    downloadConfirmSizeDesc: string;
+    downloadConfirmSizeNever: string;
+    downloadFolder: string;
+    downloadFolderDesc: string;
+    changeDownloadFolder: string;
+    resetDownloadFolder: string;
+    askWhereToSave: string;
+    askWhereToSaveDesc: string;
+    downloadRules: string;
+    downloadRulesDesc: string;
+    downloadRuleMatchPlaceholder: string;
+    addDownloadRule: string;
+    removeDownloadRule: string;
     // Advanced
     interface: string;
     bookmarksBar: string;
@@ -174,6 +195,25 @@ export interface Translations {
     completed: string;
     cancelled: string;
     interrupted: string;
+    paused: string;
+    resumeAll: string;
+    downloadsInterrupted: string;
+    queued: string;
+    retrying: string;
+    verificationFailed: string;
+    timeLeft: string;
+    elapsed: string;
+    dangerousFile: string;
+    keepFile: string;
+    discardFile: string;
+    largeDownload: string;
+    startDownload: string;
+    insufficientSpace: string;
+    moveUp: string;
+    downloadNext: string;
+    speedLimit: string;
+    speedLimitDefault: string;
+    unlimited: string;
     cancel: string;
     open: string;
     showInFolder: string;
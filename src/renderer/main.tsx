@@ -2,14 +2,18 @@ import React from 'react';
 import ReactDOM from 'react-dom/client';
 import App from './App';
 import './tauri-api'; // Инициализация Tauri API
+import { initProfileStorage } from './utils/profileStorage';
 // Стили импортируются через App.tsx -> App.css
 
 const rootElement = document.getElementById('root');
 if (rootElement) {
   const root = ReactDOM.createRoot(rootElement);
-  root.render(
-    <React.StrictMode>
-      <App />
-    </React.StrictMode>
-  );
+  // Ключи localStorage зависят от профиля — узнаём его до первого рендера
+  initProfileStorage().then(() => {
+    root.render(
+      <React.StrictMode>
+        <App />
+      </React.StrictMode>
+    );
+  });
 }
@@ -2,6 +2,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { open as shellOpen } from '@tauri-apps/plugin-shell';
+import { DownloadProgress } from './types';
 
 export const electronAPI = {
   // Window controls
@@ -18,6 +19,14 @@ export const electronAPI = {
   // Bookmarks
   getBookmarks: () => invoke('get_bookmarks'),
   setBookmarks: (bookmarks: any[]) => invoke('set_bookmarks', { bookmarks }),
+  createBookmarkFolder: (title: string, parentId?: string | null) =>
+    invoke('create_bookmark_folder', { title, parentId }),
+  moveBookmark: (id: string, parentId?: string | null, position?: number) =>
+    invoke('move_bookmark', { id, parentId, position }),
+  reorderBookmarks: (parentId: string | null, orderedIds: string[]) =>
+    invoke('reorder_bookmarks', { parentId, orderedIds }),
+  getBookmarksByTag: (tag: string) => invoke('get_bookmarks_by_tag', { tag }),
+  resolveKeyword: (input: string) => invoke<string | null>('resolve_keyword', { input }),
 
   // History
   getHistory: () => invoke('get_history'),
@@ -25,6 +34,14 @@ export const electronAPI = {
   clearHistory: () => invoke('clear_history'),
   setHistory: (history: any[]) => invoke('set_history', { history }),
 
+  // Search
+  searchHistory: (query: string, limit?: number, offset?: number, from?: number, to?: number) =>
+    invoke('search_history', { query, limit, offset, from, to }),
+  searchBookmarks: (query: string, limit?: number, offset?: number) =>
+    invoke('search_bookmarks', { query, limit, offset }),
+  autocomplete: (input: string, quickSites?: { name: string; url: string }[], limit?: number) =>
+    invoke('autocomplete', { input, quickSites, limit }),
+
   // External
   openExternal: (url: string) => shellOpen(url),
   showSaveDialog: (options: any) => invoke('show_save_dialog', { options }),
@@ -38,6 +55,14 @@ export const electronAPI = {
     return () => { unlisten.then(fn => fn()); };
   },
 
+  // Settings change listener
+  onSettingsChanged: (callback: (data: { settings: any; changed: string[] }) => void) => {
+    const unlisten = listen('settings-changed', (event: any) => {
+      callback(event.payload);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  },
+
   // Fullscreen change listener
   onFullscreenChange: (callback: (isFullscreen: boolean) => void) => {
     const unlisten = listen('fullscreen-change', (event: any) => {
@@ -65,14 +90,23 @@ export const electronAPI = {
 
   // Downloads
   getDownloads: () => invoke('get_downloads'),
-  startDownload: (url: string, filename?: string) => invoke('start_download', { url, filename }),
+  startDownload: (url: string, filename?: string, expectedDigest?: string) => invoke('start_download', { url, filename, expectedDigest }),
   cancelDownload: (id: string) => invoke('cancel_download', { id }),
   pauseDownload: (id: string) => invoke('pause_download', { id }),
   resumeDownload: (id: string) => invoke('resume_download', { id }),
-  openDownload: (path: string) => invoke('open_download', { path }),
+  resumeInterruptedDownloads: () => invoke<number>('resume_interrupted_downloads'),
+  takeInterruptedDownloads: () => invoke('take_interrupted_downloads'),
+  getDownloadQueue: () => invoke<string[]>('get_download_queue'),
+  moveDownloadInQueue: (id: string, position: number) => invoke('move_download_in_queue', { id, position }),
+  prioritizeDownload: (id: string) => invoke('prioritize_download', { id }),
+  setDownloadSpeedLimit: (id: string, bytesPerSec: number | null) => invoke('set_download_speed_limit', { id, bytesPerSec }),
+  computeDownloadHash: (id: string, algo: string) => invoke<string>('compute_download_hash', { id, algo }),
+  openDownload: (id: string) => invoke('open_download', { id }),
+  confirmDownload: (id: string) => invoke('confirm_download', { id }),
   showDownloadInFolder: (path: string) => invoke('show_download_in_folder', { path }),
   clearCompletedDownloads: () => invoke('clear_completed_downloads'),
   getDownloadsFolder: () => invoke<string>('get_downloads_folder'),
+  pickDownloadFolder: (current?: string) => invoke<string | null>('pick_download_folder', { current }),
   
   // Download events
   onDownloadStarted: (callback: (download: any) => void) => {
@@ -87,7 +121,13 @@ export const electronAPI = {
     });
     return () => { unlisten.then(fn => fn()); };
   },
-  onDownloadProgress: (callback: (data: { id: string; receivedBytes: number; totalBytes: number; speed: number }) => void) => {
+  onDownloadQueueChanged: (callback: (queue: string[]) => void) => {
+    const unlisten = listen('download-queue-changed', (event: any) => {
+      callback(event.payload);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  },
+  onDownloadProgress: (callback: (progress: DownloadProgress) => void) => {
     const unlisten = listen('download-progress', (event: any) => {
       callback(event.payload);
     });
@@ -130,6 +170,13 @@ export const electronAPI = {
   restoreSession: () => invoke('restore_session'),
   clearSession: () => invoke('clear_session'),
 
+  // Profiles
+  listProfiles: () => invoke('list_profiles'),
+  createProfile: (name: string) => invoke('create_profile', { name }),
+  renameProfile: (id: string, name: string) => invoke('rename_profile', { id, name }),
+  deleteProfile: (id: string) => invoke('delete_profile', { id }),
+  switchProfile: (id: string) => invoke('switch_profile', { id }),
+
   // WebView2 commands
   createWebView: (id: string, url: string) => invoke('create_webview', { id, url }),
   closeWebView: (id: string) => invoke('close_webview', { id }),
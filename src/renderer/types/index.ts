@@ -14,9 +14,10 @@ export interface Tab {
   lastActiveAt?: number; // Время последней активности
   thumbnail?: string; // Base64 скриншот страницы для превью
   thumbnailUpdatedAt?: number; // Время последнего обновления скриншота
+  transition?: Transition; // Как открыт текущий адрес — попадает в историю
 }
 
-export type Language = 'ru' | 'en';
+export type Language = 'ru' | 'en' | 'es' | 'fr' | 'de';
 
 export interface TabError {
   code: number;
@@ -38,14 +39,55 @@ export interface Bookmark {
   title: string;
   favicon?: string;
   createdAt: number;
+  type?: 'bookmark' | 'folder' | 'separator';
+  parentId?: string | null;
+  position?: number;
+  tags?: string[];
+  keyword?: string | null;
 }
 
+// Как пользователь попал на страницу: ввёл адрес сам или перешёл по ссылке
+export type Transition = 'link' | 'typed';
+
 export interface HistoryEntry {
   id: string;
   url: string;
   title: string;
   favicon?: string;
   visitedAt: number;
+  visitCount?: number;
+  transition?: Transition;
+}
+
+export interface Suggestion {
+  kind: 'open_tab' | 'bookmark' | 'quick_site' | 'history';
+  url: string;
+  title: string;
+  favicon?: string | null;
+  score: number;
+  tabId?: string | null;
+}
+
+export interface HighlightRange {
+  start: number;
+  end: number;
+}
+
+export interface SearchResults<T> {
+  items: T[];
+  total: number;
+}
+
+export interface HistorySearchHit extends HistoryEntry {
+  rank: number;
+  titleHighlights: HighlightRange[];
+  urlHighlights: HighlightRange[];
+}
+
+export interface BookmarkSearchHit extends Bookmark {
+  rank: number;
+  titleHighlights: HighlightRange[];
+  urlHighlights: HighlightRange[];
 }
 
 export interface Settings {
@@ -98,6 +140,10 @@ export interface Settings {
   hardwareAcceleration: boolean;
   tabSuspension: boolean;
   tabSuspensionTimeout: number;
+  maxConcurrentDownloads: number;
+  downloadConnections: number;
+  downloadSpeedLimit: number;
+  downloadConfirmSize: number;
   preloadPages: boolean;
   
   // Дополнительно
@@ -108,10 +154,36 @@ export interface Settings {
   soundEnabled: boolean;
   notificationsEnabled: boolean;
   downloadPath: string;
-  language: 'ru' | 'en';
+  askWhereToSave: boolean;
+  downloadRules: DownloadRule[];
+  language: Language;
   showWelcomeOnNextLaunch: boolean;
 }
 
+/** Папка для загрузок по типу файла */
+export interface DownloadRule {
+  // MIME-типы (application/pdf, image/*) или расширения (pdf, .tar.gz) через запятую
+  match: string;
+  // Абсолютный путь или путь от домашнего каталога (~/Pictures)
+  folder: string;
+}
+
+export interface SettingsChangedEvent {
+  settings: Settings;
+  changed: (keyof Settings)[];
+}
+
+export interface Profile {
+  id: string;
+  name: string;
+  createdAt: number;
+}
+
+export interface ProfileList {
+  profiles: Profile[];
+  active: string;
+}
+
 export interface QuickAccess {
   id: string;
   url: string;
@@ -175,6 +247,10 @@ export const defaultSettings: Settings = {
   hardwareAcceleration: true,
   tabSuspension: true,
   tabSuspensionTimeout: 30,
+  maxConcurrentDownloads: 3,
+  downloadConnections: 4,
+  downloadSpeedLimit: 0,
+  downloadConfirmSize: 0,
   preloadPages: false,
   
   // Дополнительно
@@ -185,19 +261,73 @@ export const defaultSettings: Settings = {
   soundEnabled: true,
   notificationsEnabled: true,
   downloadPath: '',
+  askWhereToSave: false,
+  downloadRules: [],
   language: 'ru',
   showWelcomeOnNextLaunch: false,
 };
 
+/** Сегмент загрузки в несколько соединений: байты [start, end) */
+export interface DownloadSegment {
+  start: number;
+  end: number;
+  received: number;
+}
+
 export interface Download {
   id: string;
   filename: string;
   url: string;
   totalBytes: number;
   receivedBytes: number;
-  state: 'progressing' | 'completed' | 'cancelled' | 'interrupted';
+  state: 'queued' | 'too_large' | 'progressing' | 'retrying' | 'paused' | 'completed' | 'needs_confirmation' | 'verification_failed' | 'cancelled' | 'interrupted' | 'insufficient_space';
   startTime: number;
   savePath?: string;
+  // Приходят в событиях прогресса при загрузке в несколько соединений
+  segments?: DownloadSegment[];
+  // Свой лимит скорости, байт/с (null — общий из настроек, 0 — без ограничений)
+  speedLimit?: number | null;
+  // Номер повтора после временной ошибки и время следующей попытки (мс)
+  retryAttempt?: number;
+  nextRetryAt?: number | null;
+  // Последняя ошибка передачи
+  error?: string | null;
+  // Ожидаемая и посчитанная контрольные суммы: 'sha256:<hex>'
+  expectedDigest?: string | null;
+  digest?: string | null;
+  // Сглаженная скорость (байт/с) и оставшееся время (с) — из событий прогресса
+  speed?: number;
+  etaSeconds?: number | null;
+  // Сколько загрузка шла, мс (без пауз и ожидания в очереди)
+  elapsedMs?: number;
+  // Чем опасен готовый файл; такую загрузку нужно подтвердить, прежде чем открыть
+  danger?: 'executable' | 'script' | 'archive' | 'mismatch' | null;
+  // Откуда взялась загрузка
+  provenance?: DownloadProvenance;
+}
+
+// Вкладка и страница, с которых началась загрузка, и ответ сервера
+export interface DownloadProvenance {
+  tabId?: string | null;
+  pageUrl?: string | null;
+  referrer?: string | null;
+  // Адреса, которые сервер перенаправил дальше, начиная с исходного
+  redirects?: string[];
+  finalUrl?: string | null;
+  responseHeaders?: Record<string, string>;
+}
+
+// Событие download-progress идущей загрузки
+export interface DownloadProgress {
+  id: string;
+  receivedBytes: number;
+  // -1, если размер неизвестен
+  totalBytes: number;
+  speed: number;
+  // null, если размер неизвестен или загрузка стоит
+  etaSeconds: number | null;
+  elapsedMs: number;
+  segments: DownloadSegment[];
 }
 
 declare global {
@@ -212,13 +342,22 @@ declare global {
       setSettings: (settings: Settings) => Promise<void>;
       getBookmarks: () => Promise<Bookmark[]>;
       setBookmarks: (bookmarks: Bookmark[]) => Promise<void>;
+      createBookmarkFolder: (title: string, parentId?: string | null) => Promise<Bookmark>;
+      moveBookmark: (id: string, parentId?: string | null, position?: number) => Promise<Bookmark[]>;
+      reorderBookmarks: (parentId: string | null, orderedIds: string[]) => Promise<Bookmark[]>;
+      getBookmarksByTag: (tag: string) => Promise<Bookmark[]>;
+      resolveKeyword: (input: string) => Promise<string | null>;
       getHistory: () => Promise<HistoryEntry[]>;
       addHistory: (entry: HistoryEntry) => Promise<void>;
       clearHistory: () => Promise<void>;
+      searchHistory: (query: string, limit?: number, offset?: number, from?: number, to?: number) => Promise<SearchResults<HistorySearchHit>>;
+      searchBookmarks: (query: string, limit?: number, offset?: number) => Promise<SearchResults<BookmarkSearchHit>>;
+      autocomplete: (input: string, quickSites?: { name: string; url: string }[], limit?: number) => Promise<Suggestion[]>;
       openExternal: (url: string) => Promise<void>;
       showSaveDialog: (options: any) => Promise<any>;
       showError: (title: string, message: string) => Promise<void>;
       onShortcut: (callback: (action: string) => void) => () => void;
+      onSettingsChanged: (callback: (data: SettingsChangedEvent) => void) => () => void;
       onFullscreenChange: (callback: (isFullscreen: boolean) => void) => () => void;
       onOpenUrl: (callback: (url: string) => void) => () => void;
       exportBookmarks: (bookmarks: Bookmark[]) => Promise<boolean>;
@@ -230,10 +369,29 @@ declare global {
       // Downloads
       getDownloads: () => Promise<Download[]>;
       onDownloadUpdate: (callback: (download: Download) => void) => () => void;
+      onDownloadProgress: (callback: (progress: DownloadProgress) => void) => () => void;
       cancelDownload: (id: string) => Promise<void>;
-      openDownload: (path: string) => Promise<void>;
+      pauseDownload: (id: string) => Promise<void>;
+      resumeDownload: (id: string) => Promise<void>;
+      resumeInterruptedDownloads: () => Promise<number>;
+      // Загрузки, оборванные закрытием приложения (отдаются один раз за запуск)
+      takeInterruptedDownloads: () => Promise<Download[]>;
+      // Очередь загрузок: ID ожидающих загрузок в порядке запуска
+      getDownloadQueue: () => Promise<string[]>;
+      onDownloadQueueChanged: (callback: (queue: string[]) => void) => () => void;
+      moveDownloadInQueue: (id: string, position: number) => Promise<void>;
+      prioritizeDownload: (id: string) => Promise<void>;
+      setDownloadSpeedLimit: (id: string, bytesPerSec: number | null) => Promise<void>;
+      // Контрольная сумма скачанного файла: algo — 'sha256', 'sha1' или 'md5'
+      computeDownloadHash: (id: string, algo: 'sha256' | 'sha1' | 'md5') => Promise<string>;
+      // Открыть файл загрузки (опасный — только после confirmDownload)
+      openDownload: (id: string) => Promise<void>;
+      confirmDownload: (id: string) => Promise<void>;
       showDownloadInFolder: (path: string) => Promise<void>;
       clearCompletedDownloads: () => Promise<void>;
+      // Папка загрузок по умолчанию (из настроек или системная)
+      getDownloadsFolder: () => Promise<string>;
+      pickDownloadFolder: (current?: string) => Promise<string | null>;
       // Browser import
       importFromBrowser: (browser: 'chrome' | 'firefox' | 'edge' | 'zen') => Promise<{ bookmarks: Bookmark[], history: HistoryEntry[] } | null>;
       detectBrowsers: () => Promise<DetectedBrowser[]>;
@@ -249,6 +407,13 @@ declare global {
       saveSession: (sessionData: any) => Promise<boolean>;
       restoreSession: () => Promise<any | null>;
       clearSession: () => Promise<boolean>;
+      // Profiles
+      listProfiles: () => Promise<ProfileList>;
+      createProfile: (name: string) => Promise<Profile>;
+      renameProfile: (id: string, name: string) => Promise<Profile>;
+      deleteProfile: (id: string) => Promise<void>;
+      // Перезапускает приложение в выбранном профиле — сессию нужно сохранить заранее
+      switchProfile: (id: string) => Promise<void>;
       // Partition sessions
       getPartitionSession: (partition: string) => Promise<boolean>;
       // WebView2 commands
@@ -0,0 +1,81 @@
+import { Bookmark } from '../types';
+
+/**
+ * Объединение импортированных закладок с существующими
+ *
+ * Папка с тем же путём (цепочка родителей + название) уже есть — её
+ * содержимое переносится в существующую папку. Закладки с уже сохранённым
+ * адресом пропускаются, а новые папки, оставшиеся без закладок, не
+ * добавляются — повторный импорт ничего не дублирует.
+ */
+export const mergeImportedBookmarks = (existing: Bookmark[], imported: Bookmark[]): Bookmark[] => {
+  const PATH_SEPARATOR = '\u0000';
+  const isFolder = (b: Bookmark) => b.type === 'folder';
+
+  // Пути существующих папок
+  const existingById = new Map(existing.map(b => [b.id, b]));
+  const pathOf = (folder: Bookmark, byId: Map<string, Bookmark>): string => {
+    const parent = folder.parentId ? byId.get(folder.parentId) : undefined;
+    return (parent ? pathOf(parent, byId) + PATH_SEPARATOR : '') + folder.title;
+  };
+  const folderByPath = new Map<string, string>();
+  existing.filter(isFolder).forEach(folder => folderByPath.set(pathOf(folder, existingById), folder.id));
+
+  const knownUrls = new Set(existing.filter(b => !isFolder(b) && b.type !== 'separator').map(b => b.url));
+  const existingFolderIds = new Set(existing.filter(isFolder).map(b => b.id));
+
+  // Следующая позиция в каждой папке (null — корень)
+  const nextPosition = new Map<string | null, number>();
+  existing.forEach(b => {
+    const parent = b.parentId ?? null;
+    nextPosition.set(parent, Math.max(nextPosition.get(parent) ?? 0, (b.position ?? 0) + 1));
+  });
+
+  // Импортированные папки → папки результата
+  const importedById = new Map(imported.map(b => [b.id, b]));
+  const folderIds = new Map<string, string>();
+  const resolveFolder = (folder: Bookmark): string => {
+    const known = folderIds.get(folder.id);
+    if (known) return known;
+    const path = pathOf(folder, importedById);
+    const target = folderByPath.get(path) ?? folder.id;
+    folderByPath.set(path, target);
+    folderIds.set(folder.id, target);
+    return target;
+  };
+  const parentOf = (b: Bookmark): string | null => {
+    const parent = b.parentId ? importedById.get(b.parentId) : undefined;
+    return parent ? resolveFolder(parent) : null;
+  };
+
+  const added: Bookmark[] = [];
+  const addedIds = new Set<string>();
+  const ordered = [...imported].sort((a, b) => (a.position ?? 0) - (b.position ?? 0));
+  for (const item of ordered) {
+    const parentId = parentOf(item);
+    if (isFolder(item)) {
+      const target = resolveFolder(item);
+      if (target !== item.id || addedIds.has(target)) continue;
+    } else if (item.type === 'separator') {
+      // Разделители уже существующих папок у пользователя и так есть
+      if (parentId === null || existingFolderIds.has(parentId)) continue;
+    } else {
+      if (knownUrls.has(item.url)) continue;
+      knownUrls.add(item.url);
+    }
+
+    const inExisting = parentId === null || existingFolderIds.has(parentId);
+    const position = inExisting ? nextPosition.get(parentId) ?? 0 : item.position;
+    if (inExisting) nextPosition.set(parentId, (position ?? 0) + 1);
+    added.push({ ...item, parentId, position });
+    addedIds.add(item.id);
+  }
+
+  // Новые папки без закладок (в том числе вложенных) не нужны
+  const hasBookmarks = (folderId: string): boolean =>
+    added.some(b => b.parentId === folderId && (isFolder(b) ? hasBookmarks(b.id) : b.type !== 'separator'));
+  const emptyFolders = new Set(added.filter(b => isFolder(b) && !hasBookmarks(b.id)).map(b => b.id));
+  const kept = added.filter(b => !emptyFolders.has(b.id) && !(b.parentId && emptyFolders.has(b.parentId)));
+
+  return [...existing, ...kept];
+};
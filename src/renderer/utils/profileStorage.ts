@@ -0,0 +1,29 @@
+/**
+ * localStorage текущего профиля
+ *
+ * localStorage главного окна один на все профили, поэтому ключи хранятся
+ * с ID профиля: `profile:<id>:<ключ>`. Профиль по умолчанию использует ключи
+ * без префикса — там лежат данные, сохранённые до появления профилей.
+ * Профиль не меняется до перезапуска приложения, поэтому его ID узнаём
+ * один раз до первого рендера (см. main.tsx).
+ */
+
+const DEFAULT_PROFILE = 'default';
+
+let activeProfile = DEFAULT_PROFILE;
+
+export const initProfileStorage = async () => {
+  try {
+    const { active } = await window.electronAPI.listProfiles();
+    activeProfile = active;
+  } catch (e) {
+    console.warn('Failed to get active profile, using default:', e);
+  }
+};
+
+export const profileStorage = {
+  /** Ключ localStorage для ключа профиля — например, для сравнения в событии storage */
+  key: (key: string) => (activeProfile === DEFAULT_PROFILE ? key : `profile:${activeProfile}:${key}`),
+  getItem: (key: string) => localStorage.getItem(profileStorage.key(key)),
+  setItem: (key: string, value: string) => localStorage.setItem(profileStorage.key(key), value),
+};